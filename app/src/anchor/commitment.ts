@@ -0,0 +1,57 @@
+import { PublicKey } from '@solana/web3.js';
+import { Buffer } from 'buffer';
+import { keccak256 } from 'ethereum-cryptography/keccak';
+
+// Must match COMMITMENT_DOMAIN, SOLUTION_DOMAIN and COMMITMENT_VERSION in the program
+const COMMITMENT_DOMAIN = Buffer.from('riddle-rush:submission:v1');
+const SOLUTION_DOMAIN = Buffer.from('riddle-rush:solution:v1');
+export const COMMITMENT_VERSION = 1;
+
+const lengthPrefixed = (text: string): Buffer => {
+  const bytes = Buffer.from(text, 'utf8');
+  const length = Buffer.alloc(4);
+  length.writeUInt32LE(bytes.length);
+  return Buffer.concat([length, bytes]);
+};
+
+// Version 1 submission commitment, the same bytes as submission_commitment in the program:
+// keccak256(domain || challenge || submitter || u32 LE answer length || answer || 32-byte salt)
+export const submissionCommitment = (
+  challenge: PublicKey,
+  submitter: PublicKey,
+  answer: string,
+  salt: Uint8Array,
+): Uint8Array => keccak256(Buffer.concat([
+  COMMITMENT_DOMAIN,
+  challenge.toBuffer(),
+  submitter.toBuffer(),
+  lengthPrefixed(answer),
+  Buffer.from(salt),
+]));
+
+// Text riddle solution hash, the same bytes as solution_commitment in the program:
+// keccak256(domain || challenge || u32 LE solution length || solution || 32-byte salt)
+export const solutionCommitment = (challenge: PublicKey, solution: string, salt: Uint8Array): Uint8Array =>
+  keccak256(Buffer.concat([SOLUTION_DOMAIN, challenge.toBuffer(), lengthPrefixed(solution), Buffer.from(salt)]));
+
+export const generateSalt = (): Uint8Array => crypto.getRandomValues(new Uint8Array(32));
+
+// Salts are kept per challenge and submitter so switching wallets can't overwrite one
+const saltKey = (challenge: PublicKey, submitter: PublicKey) =>
+  `submission_salt_${challenge.toBase58()}_${submitter.toBase58()}`;
+
+export const saveSalt = (challenge: PublicKey, submitter: PublicKey, salt: Uint8Array) => {
+  localStorage.setItem(saltKey(challenge, submitter), Buffer.from(salt).toString('hex'));
+};
+
+export const loadSalt = (challenge: PublicKey, submitter: PublicKey): Uint8Array | null => {
+  const hex = localStorage.getItem(saltKey(challenge, submitter));
+  return parseSalt(hex ?? '');
+};
+
+// Parses a salt the player saved by hand, null unless it is exactly 32 bytes of hex
+export const parseSalt = (hex: string): Uint8Array | null => {
+  const trimmed = hex.trim();
+  if (!/^[0-9a-fA-F]{64}$/.test(trimmed)) return null;
+  return new Uint8Array(Buffer.from(trimmed, 'hex'));
+};
@@ -13,6 +13,30 @@ export type RiddleRush = {
     "description": "Created with Anchor"
   },
   "instructions": [
+    {
+      "name": "acceptAuthority",
+      "discriminator": [
+        107,
+        86,
+        198,
+        91,
+        33,
+        12,
+        107,
+        160
+      ],
+      "accounts": [
+        {
+          "name": "pendingAuthority",
+          "signer": true
+        },
+        {
+          "name": "globalConfig",
+          "writable": true
+        }
+      ],
+      "args": []
+    },
     {
       "name": "challengeSolutionReveal",
       "discriminator": [
@@ -31,44 +55,68 @@ export type RiddleRush = {
           "writable": true,
           "signer": true
         },
+        {
+          "name": "globalConfig"
+        },
         {
           "name": "challengeAccount",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  99,
-                  104,
-                  97,
-                  108,
-                  108,
-                  101,
-                  110,
-                  103,
-                  101
-                ]
-              },
-              {
-                "kind": "arg",
-                "path": "id"
-              }
-            ]
-          }
+          "writable": true
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "systemProgram"
         }
       ],
       "args": [
         {
           "name": "id",
           "type": "u64"
+        },
+        {
+          "name": "plaintextSolution",
+          "type": "string"
+        },
+        {
+          "name": "salt",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
         }
       ]
     },
+    {
+      "name": "closeSubmission",
+      "discriminator": [
+        204,
+        237,
+        166,
+        65,
+        57,
+        7,
+        11,
+        105
+      ],
+      "accounts": [
+        {
+          "name": "submitter",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "globalConfig"
+        },
+        {
+          "name": "challengeAccount"
+        },
+        {
+          "name": "submissionAccount",
+          "writable": true
+        }
+      ],
+      "args": []
+    },
     {
       "name": "createChallenge",
       "discriminator": [
@@ -89,60 +137,32 @@ export type RiddleRush = {
         },
         {
           "name": "globalConfig",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  103,
-                  108,
-                  111,
-                  98,
-                  97,
-                  108,
-                  95,
-                  99,
-                  111,
-                  110,
-                  102,
-                  105,
-                  103
-                ]
-              }
-            ]
-          }
+          "writable": true
         },
         {
           "name": "challengeAccount",
+          "writable": true
+        },
+        {
+          "name": "systemProgram"
+        },
+        {
+          "name": "mint",
+          "optional": true
+        },
+        {
+          "name": "vault",
           "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  99,
-                  104,
-                  97,
-                  108,
-                  108,
-                  101,
-                  110,
-                  103,
-                  101
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "global_config.next_challenge_id",
-                "account": "globalConfig"
-              }
-            ]
-          }
+          "optional": true
+        },
+        {
+          "name": "setterTokenAccount",
+          "writable": true,
+          "optional": true
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "tokenProgram",
+          "optional": true
         }
       ],
       "args": [
@@ -165,6 +185,43 @@ export type RiddleRush = {
         {
           "name": "entryFee",
           "type": "u64"
+        },
+        {
+          "name": "kind",
+          "type": {
+            "defined": {
+              "name": "challengeKind"
+            }
+          }
+        },
+        {
+          "name": "solutionHash",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
+        },
+        {
+          "name": "normalization",
+          "type": {
+            "defined": {
+              "name": "answerNormalization"
+            }
+          }
+        },
+        {
+          "name": "setterCutBps",
+          "type": "u16"
+        },
+        {
+          "name": "payoutStrategy",
+          "type": {
+            "defined": {
+              "name": "payoutStrategy"
+            }
+          }
         }
       ]
     },
@@ -186,67 +243,37 @@ export type RiddleRush = {
           "writable": true,
           "signer": true
         },
+        {
+          "name": "globalConfig"
+        },
         {
           "name": "challengeAccount",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  99,
-                  104,
-                  97,
-                  108,
-                  108,
-                  101,
-                  110,
-                  103,
-                  101
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "challenge_account.id",
-                "account": "challengeAccount"
-              }
-            ]
-          }
+          "writable": true
         },
         {
           "name": "submissionAccount",
+          "writable": true
+        },
+        {
+          "name": "systemProgram"
+        },
+        {
+          "name": "mint",
+          "optional": true
+        },
+        {
+          "name": "vault",
           "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  115,
-                  117,
-                  98,
-                  109,
-                  105,
-                  115,
-                  115,
-                  105,
-                  111,
-                  110
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "challengeAccount"
-              },
-              {
-                "kind": "account",
-                "path": "submitter"
-              }
-            ]
-          }
+          "optional": true
+        },
+        {
+          "name": "submitterTokenAccount",
+          "writable": true,
+          "optional": true
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "tokenProgram",
+          "optional": true
         }
       ],
       "args": [
@@ -258,6 +285,10 @@ export type RiddleRush = {
               32
             ]
           }
+        },
+        {
+          "name": "commitmentVersion",
+          "type": "u8"
         }
       ]
     },
@@ -281,37 +312,153 @@ export type RiddleRush = {
         },
         {
           "name": "globalConfig",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  103,
-                  108,
-                  111,
-                  98,
-                  97,
-                  108,
-                  95,
-                  99,
-                  111,
-                  110,
-                  102,
-                  105,
-                  103
-                ]
-              }
-            ]
+          "writable": true
+        },
+        {
+          "name": "program"
+        },
+        {
+          "name": "programData"
+        },
+        {
+          "name": "systemProgram"
+        }
+      ],
+      "args": [
+        {
+          "name": "treasury",
+          "type": "pubkey"
+        },
+        {
+          "name": "protocolFeeBps",
+          "type": "u16"
+        },
+        {
+          "name": "minSetterCutBps",
+          "type": "u16"
+        },
+        {
+          "name": "maxSetterCutBps",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "proposeAuthority",
+      "discriminator": [
+        20,
+        148,
+        236,
+        198,
+        76,
+        119,
+        99,
+        142
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "globalConfig",
+          "writable": true
+        }
+      ],
+      "args": [
+        {
+          "name": "newAuthority",
+          "type": {
+            "option": "pubkey"
           }
+        }
+      ]
+    },
+    {
+      "name": "refund",
+      "discriminator": [
+        2,
+        96,
+        183,
+        251,
+        63,
+        208,
+        46,
+        46
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "signer": true
+        },
+        {
+          "name": "globalConfig"
+        },
+        {
+          "name": "challengeAccount",
+          "writable": true
+        },
+        {
+          "name": "submitter",
+          "writable": true
+        },
+        {
+          "name": "submissionAccount",
+          "writable": true
+        },
+        {
+          "name": "systemProgram"
+        },
+        {
+          "name": "mint",
+          "optional": true
+        },
+        {
+          "name": "vault",
+          "writable": true,
+          "optional": true
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "submitterTokenAccount",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "tokenProgram",
+          "optional": true
         }
       ],
       "args": []
     },
+    {
+      "name": "setPaused",
+      "discriminator": [
+        91,
+        60,
+        125,
+        192,
+        176,
+        225,
+        166,
+        218
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "globalConfig",
+          "writable": true
+        }
+      ],
+      "args": [
+        {
+          "name": "pausedOperations",
+          "type": "u8"
+        }
+      ]
+    },
     {
       "name": "setterClaim",
       "discriminator": [
@@ -328,41 +475,35 @@ export type RiddleRush = {
         {
           "name": "setter",
           "writable": true,
-          "signer": true,
-          "relations": [
-            "challengeAccount"
-          ]
+          "signer": true
+        },
+        {
+          "name": "globalConfig"
         },
         {
           "name": "challengeAccount",
+          "writable": true
+        },
+        {
+          "name": "systemProgram"
+        },
+        {
+          "name": "mint",
+          "optional": true
+        },
+        {
+          "name": "vault",
           "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  99,
-                  104,
-                  97,
-                  108,
-                  108,
-                  101,
-                  110,
-                  103,
-                  101
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "challenge_account.id",
-                "account": "challengeAccount"
-              }
-            ]
-          }
+          "optional": true
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "setterTokenAccount",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "tokenProgram",
+          "optional": true
         }
       ],
       "args": []
@@ -385,36 +526,34 @@ export type RiddleRush = {
           "writable": true,
           "signer": true
         },
+        {
+          "name": "globalConfig"
+        },
         {
           "name": "challengeAccount",
+          "writable": true
+        },
+        {
+          "name": "systemProgram"
+        },
+        {
+          "name": "mint",
           "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  99,
-                  104,
-                  97,
-                  108,
-                  108,
-                  101,
-                  110,
-                  103,
-                  101
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "challenge_account.id",
-                "account": "challengeAccount"
-              }
-            ]
-          }
+          "optional": true
+        },
+        {
+          "name": "vault",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "setterTokenAccount",
+          "writable": true,
+          "optional": true
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "tokenProgram",
+          "optional": true
         }
       ],
       "args": []
@@ -437,73 +576,30 @@ export type RiddleRush = {
           "writable": true,
           "signer": true
         },
+        {
+          "name": "globalConfig"
+        },
         {
           "name": "challengeAccount",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  99,
-                  104,
-                  97,
-                  108,
-                  108,
-                  101,
-                  110,
-                  103,
-                  101
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "challenge_account.id",
-                "account": "challengeAccount"
-              }
-            ]
-          }
+          "writable": true
         },
         {
           "name": "submissionAccount",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  115,
-                  117,
-                  98,
-                  109,
-                  105,
-                  115,
-                  115,
-                  105,
-                  111,
-                  110
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "challengeAccount"
-              },
-              {
-                "kind": "account",
-                "path": "submitter"
-              }
-            ]
-          }
+          "writable": true
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "systemProgram"
         }
       ],
       "args": [
         {
-          "name": "nonce",
-          "type": "string"
+          "name": "salt",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
         },
         {
           "name": "plaintextAnswer",
@@ -529,70 +625,167 @@ export type RiddleRush = {
           "writable": true,
           "signer": true
         },
+        {
+          "name": "globalConfig"
+        },
         {
           "name": "challengeAccount",
+          "writable": true
+        },
+        {
+          "name": "submissionAccount",
+          "writable": true
+        },
+        {
+          "name": "systemProgram"
+        },
+        {
+          "name": "mint",
+          "optional": true
+        },
+        {
+          "name": "vault",
           "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  99,
-                  104,
-                  97,
-                  108,
-                  108,
-                  101,
-                  110,
-                  103,
-                  101
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "challenge_account.id",
-                "account": "challengeAccount"
-              }
-            ]
-          }
+          "optional": true
+        },
+        {
+          "name": "submitterTokenAccount",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "tokenProgram",
+          "optional": true
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "sweepSubmission",
+      "discriminator": [
+        23,
+        161,
+        197,
+        156,
+        147,
+        27,
+        251,
+        12
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "signer": true
+        },
+        {
+          "name": "globalConfig"
+        },
+        {
+          "name": "challengeAccount"
+        },
+        {
+          "name": "submitter",
+          "writable": true
         },
         {
           "name": "submissionAccount",
+          "writable": true
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "treasuryClaim",
+      "discriminator": [
+        15,
+        48,
+        191,
+        253,
+        224,
+        79,
+        251,
+        205
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "signer": true
+        },
+        {
+          "name": "globalConfig"
+        },
+        {
+          "name": "treasury",
+          "writable": true
+        },
+        {
+          "name": "challengeAccount",
+          "writable": true
+        },
+        {
+          "name": "systemProgram"
+        },
+        {
+          "name": "mint",
+          "optional": true
+        },
+        {
+          "name": "vault",
           "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  115,
-                  117,
-                  98,
-                  109,
-                  105,
-                  115,
-                  115,
-                  105,
-                  111,
-                  110
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "challengeAccount"
-              },
-              {
-                "kind": "account",
-                "path": "submitter"
-              }
-            ]
-          }
+          "optional": true
+        },
+        {
+          "name": "treasuryTokenAccount",
+          "writable": true,
+          "optional": true
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "tokenProgram",
+          "optional": true
         }
       ],
       "args": []
+    },
+    {
+      "name": "updateConfig",
+      "discriminator": [
+        29,
+        158,
+        252,
+        191,
+        10,
+        83,
+        219,
+        99
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "globalConfig",
+          "writable": true
+        }
+      ],
+      "args": [
+        {
+          "name": "treasury",
+          "type": "pubkey"
+        },
+        {
+          "name": "protocolFeeBps",
+          "type": "u16"
+        },
+        {
+          "name": "minSetterCutBps",
+          "type": "u16"
+        },
+        {
+          "name": "maxSetterCutBps",
+          "type": "u16"
+        }
+      ]
     }
   ],
   "accounts": [
@@ -636,6 +829,138 @@ export type RiddleRush = {
       ]
     }
   ],
+  "events": [
+    {
+      "discriminator": [
+        228,
+        8,
+        12,
+        154,
+        182,
+        5,
+        139,
+        91
+      ],
+      "name": "challengeClosed"
+    },
+    {
+      "discriminator": [
+        166,
+        178,
+        174,
+        178,
+        11,
+        172,
+        98,
+        243
+      ],
+      "name": "challengeCreated"
+    },
+    {
+      "discriminator": [
+        219,
+        125,
+        133,
+        9,
+        173,
+        163,
+        4,
+        94
+      ],
+      "name": "setterClaimed"
+    },
+    {
+      "discriminator": [
+        55,
+        134,
+        47,
+        199,
+        212,
+        142,
+        200,
+        198
+      ],
+      "name": "solutionRevealed"
+    },
+    {
+      "discriminator": [
+        114,
+        144,
+        40,
+        111,
+        181,
+        187,
+        226,
+        1
+      ],
+      "name": "submissionClosed"
+    },
+    {
+      "discriminator": [
+        27,
+        207,
+        175,
+        100,
+        160,
+        225,
+        121,
+        79
+      ],
+      "name": "submissionCreated"
+    },
+    {
+      "discriminator": [
+        80,
+        38,
+        232,
+        20,
+        36,
+        159,
+        199,
+        139
+      ],
+      "name": "submissionRefunded"
+    },
+    {
+      "discriminator": [
+        50,
+        0,
+        22,
+        174,
+        138,
+        227,
+        114,
+        145
+      ],
+      "name": "submissionRevealed"
+    },
+    {
+      "discriminator": [
+        106,
+        186,
+        59,
+        161,
+        149,
+        84,
+        250,
+        34
+      ],
+      "name": "submitterClaimed"
+    },
+    {
+      "discriminator": [
+        59,
+        99,
+        64,
+        13,
+        118,
+        14,
+        251,
+        106
+      ],
+      "name": "treasuryClaimed"
+    }
+  ],
   "errors": [
     {
       "code": 6000,
@@ -716,91 +1041,646 @@ export type RiddleRush = {
       "code": 6015,
       "name": "zeroCorrectSubmissions",
       "msg": "No correct submissions."
-    }
-  ],
-  "types": [
+    },
     {
-      "name": "challengeAccount",
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "id",
-            "type": "u64"
-          },
-          {
-            "name": "question",
-            "type": "string"
-          },
-          {
-            "name": "solution",
-            "type": "string"
-          },
-          {
-            "name": "submissionDeadline",
-            "type": "i64"
-          },
-          {
-            "name": "answerRevealDeadline",
-            "type": "i64"
-          },
-          {
-            "name": "claimDeadline",
-            "type": "i64"
-          },
-          {
-            "name": "entryFee",
-            "type": "u64"
-          },
-          {
-            "name": "setter",
-            "type": "pubkey"
-          },
-          {
-            "name": "pot",
-            "type": "u64"
-          },
-          {
-            "name": "setterCutClaimed",
-            "type": "bool"
-          },
-          {
-            "name": "bump",
-            "type": "u8"
-          },
-          {
-            "name": "correctSubmissions",
-            "type": "u64"
-          }
-        ]
-      }
+      "code": 6016,
+      "name": "bytecodeTooLong",
+      "msg": "The compiled question is too long, max length: {MAX_BYTECODE_LENGTH}"
     },
     {
-      "name": "globalConfig",
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "nextChallengeId",
-            "type": "u64"
-          },
-          {
-            "name": "bump",
-            "type": "u8"
-          }
-        ]
-      }
+      "code": 6017,
+      "name": "expressionTooDeep",
+      "msg": "The question is nested too deeply, max depth: {MAX_EXPRESSION_DEPTH}"
     },
     {
-      "name": "submissionAccount",
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "challengeId",
-            "type": "u64"
-          },
-          {
+      "code": 6018,
+      "name": "expressionTooManyTokens",
+      "msg": "The question has too many tokens, max tokens: {MAX_EXPRESSION_TOKENS}"
+    },
+    {
+      "code": 6019,
+      "name": "expressionTooManyOperations",
+      "msg": "The question has too many operations, max operations: {MAX_EXPRESSION_OPERATIONS}"
+    },
+    {
+      "code": 6020,
+      "name": "expressionInvalidCharacter",
+      "msg": "The question contains an invalid character."
+    },
+    {
+      "code": 6021,
+      "name": "expressionInvalidNumber",
+      "msg": "The question contains an invalid number."
+    },
+    {
+      "code": 6022,
+      "name": "expressionUnexpectedEnd",
+      "msg": "The question ends unexpectedly."
+    },
+    {
+      "code": 6023,
+      "name": "expressionDivisionByZero",
+      "msg": "The question divides by zero."
+    },
+    {
+      "code": 6024,
+      "name": "expressionOverflow",
+      "msg": "The question overflows 128-bit arithmetic."
+    },
+    {
+      "code": 6025,
+      "name": "expressionNonIntegerExponent",
+      "msg": "Exponents in the question must be integers."
+    },
+    {
+      "code": 6026,
+      "name": "expressionUnknownFunction",
+      "msg": "The question calls an unknown function."
+    },
+    {
+      "code": 6027,
+      "name": "expressionUnknownConstant",
+      "msg": "The question uses an unknown constant."
+    },
+    {
+      "code": 6028,
+      "name": "expressionWrongArgumentCount",
+      "msg": "A function in the question has the wrong number of arguments."
+    },
+    {
+      "code": 6029,
+      "name": "expressionInvalidArgument",
+      "msg": "A function in the question has an invalid argument."
+    },
+    {
+      "code": 6030,
+      "name": "expressionInvalidBytecode",
+      "msg": "The compiled question is invalid."
+    },
+    {
+      "code": 6031,
+      "name": "expressionUnexpectedToken",
+      "msg": "The question contains an unexpected token."
+    },
+    {
+      "code": 6032,
+      "name": "missingSolutionHash",
+      "msg": "A text riddle needs a committed solution hash."
+    },
+    {
+      "code": 6033,
+      "name": "solutionHashMismatch",
+      "msg": "The solution doesn't match the committed solution hash."
+    },
+    {
+      "code": 6034,
+      "name": "emptySolution",
+      "msg": "The solution must not be empty."
+    },
+    {
+      "code": 6035,
+      "name": "tooManyAlternates",
+      "msg": "Too many alternate answers, max alternates: {MAX_ALTERNATES}"
+    },
+    {
+      "code": 6036,
+      "name": "alternateTooLong",
+      "msg": "An alternate answer is too long, max length: {MAX_ALTERNATE_LENGTH}"
+    },
+    {
+      "code": 6037,
+      "name": "tokenAccountsMismatch",
+      "msg": "The token accounts don't match the challenge's mint."
+    },
+    {
+      "code": 6038,
+      "name": "mintMismatch",
+      "msg": "The mint doesn't match the challenge's mint."
+    },
+    {
+      "code": 6039,
+      "name": "invalidFeeConfig",
+      "msg": "Fees and setter cuts must be at most {BPS_DENOMINATOR} basis points in total, with the minimum setter cut below the maximum."
+    },
+    {
+      "code": 6040,
+      "name": "setterCutOutOfRange",
+      "msg": "The setter cut is outside the range allowed by the global config."
+    },
+    {
+      "code": 6041,
+      "name": "notUpgradeAuthority",
+      "msg": "Only the program's upgrade authority can initialize the config."
+    },
+    {
+      "code": 6042,
+      "name": "notPendingAuthority",
+      "msg": "The signer is not the pending authority."
+    },
+    {
+      "code": 6043,
+      "name": "programPaused",
+      "msg": "This operation is paused."
+    },
+    {
+      "code": 6044,
+      "name": "invalidPauseMask",
+      "msg": "Unknown operations in the pause mask, all operations: {PAUSE_ALL}"
+    },
+    {
+      "code": 6045,
+      "name": "unsupportedCommitmentVersion",
+      "msg": "Unsupported submission commitment version."
+    },
+    {
+      "code": 6046,
+      "name": "submissionStillClaimable",
+      "msg": "The submission can still claim a share of the pot, claim it instead of closing it."
+    },
+    {
+      "code": 6047,
+      "name": "invalidPayoutWeights",
+      "msg": "Top-K payouts need between 1 and {MAX_PAYOUT_RANKS} weights, all above zero."
+    },
+    {
+      "code": 6048,
+      "name": "notAWinner",
+      "msg": "The answer is correct but doesn't win a share under the challenge's payout strategy."
+    },
+    {
+      "code": 6049,
+      "name": "challengeResolved",
+      "msg": "The solution was revealed, claim the prize or refund with submitter_claim instead."
+    },
+    {
+      "code": 6050,
+      "name": "solutionRevealDeadlinePassed",
+      "msg": "The solution can't be revealed after the answer reveal deadline."
+    },
+    {
+      "code": 6051,
+      "name": "submissionNotRevealed",
+      "msg": "Nobody answered correctly and this answer was never revealed, so it doesn't share the refund."
+    },
+    {
+      "code": 6052,
+      "name": "payoutExceedsPot",
+      "msg": "The payout would exceed the pot."
+    },
+    {
+      "code": 6053,
+      "name": "alternatesOnTextRiddle",
+      "msg": "Text riddles can't have alternates, they would be public before the solution is revealed."
+    },
+    {
+      "code": 6054,
+      "name": "unsupportedMint",
+      "msg": "The mint has a freeze authority or Token-2022 extensions other than a transfer fee."
+    },
+    {
+      "code": 6055,
+      "name": "answerRevealDeadlinePassed",
+      "msg": "Answers can't be revealed after the answer reveal deadline."
+    },
+    {
+      "code": 6056,
+      "name": "missingLotterySeedHash",
+      "msg": "A lottery needs the hash of the setter's seed."
+    },
+    {
+      "code": 6057,
+      "name": "lotterySeedMismatch",
+      "msg": "The salt doesn't match the lottery's seed hash."
+    }
+  ],
+  "types": [
+    {
+      "name": "answerNormalization",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "trim",
+            "type": "bool"
+          },
+          {
+            "name": "lowercase",
+            "type": "bool"
+          },
+          {
+            "name": "nfkc",
+            "type": "bool"
+          },
+          {
+            "name": "numeric",
+            "type": "bool"
+          },
+          {
+            "name": "alternates",
+            "type": {
+              "vec": "string"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "challengeAccount",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "id",
+            "type": "u64"
+          },
+          {
+            "name": "kind",
+            "type": {
+              "defined": {
+                "name": "challengeKind"
+              }
+            }
+          },
+          {
+            "name": "question",
+            "type": "string"
+          },
+          {
+            "name": "bytecode",
+            "type": "bytes"
+          },
+          {
+            "name": "solution",
+            "type": "string"
+          },
+          {
+            "name": "solutionHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "normalization",
+            "type": {
+              "defined": {
+                "name": "answerNormalization"
+              }
+            }
+          },
+          {
+            "name": "submissionDeadline",
+            "type": "i64"
+          },
+          {
+            "name": "answerRevealDeadline",
+            "type": "i64"
+          },
+          {
+            "name": "claimDeadline",
+            "type": "i64"
+          },
+          {
+            "name": "entryFee",
+            "type": "u64"
+          },
+          {
+            "name": "mint",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "setter",
+            "type": "pubkey"
+          },
+          {
+            "name": "pot",
+            "type": "u64"
+          },
+          {
+            "name": "setterCutBps",
+            "type": "u16"
+          },
+          {
+            "name": "setterCutClaimed",
+            "type": "bool"
+          },
+          {
+            "name": "protocolFeeBps",
+            "type": "u16"
+          },
+          {
+            "name": "protocolFeeClaimed",
+            "type": "bool"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "correctSubmissions",
+            "type": "u64"
+          },
+          {
+            "name": "submissionCount",
+            "type": "u64"
+          },
+          {
+            "name": "payoutStrategy",
+            "type": {
+              "defined": {
+                "name": "payoutStrategy"
+              }
+            }
+          },
+          {
+            "name": "rankedSubmissions",
+            "type": {
+              "vec": "u64"
+            }
+          },
+          {
+            "name": "lotterySeed",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "totalPaidOut",
+            "type": "u64"
+          },
+          {
+            "name": "claimsCount",
+            "type": "u64"
+          },
+          {
+            "name": "revealedCount",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "challengeClosed",
+      "type": {
+        "fields": [
+          {
+            "name": "challenge",
+            "type": "pubkey"
+          },
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
+            "name": "setter",
+            "type": "pubkey"
+          },
+          {
+            "name": "lamports",
+            "type": "u64"
+          },
+          {
+            "name": "tokens",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "challengeCreated",
+      "type": {
+        "fields": [
+          {
+            "name": "challenge",
+            "type": "pubkey"
+          },
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
+            "name": "setter",
+            "type": "pubkey"
+          },
+          {
+            "name": "kind",
+            "type": {
+              "defined": {
+                "name": "challengeKind"
+              }
+            }
+          },
+          {
+            "name": "question",
+            "type": "string"
+          },
+          {
+            "name": "mint",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "entryFee",
+            "type": "u64"
+          },
+          {
+            "name": "pot",
+            "type": "u64"
+          },
+          {
+            "name": "submissionDeadline",
+            "type": "i64"
+          },
+          {
+            "name": "answerRevealDeadline",
+            "type": "i64"
+          },
+          {
+            "name": "claimDeadline",
+            "type": "i64"
+          },
+          {
+            "name": "setterCutBps",
+            "type": "u16"
+          },
+          {
+            "name": "protocolFeeBps",
+            "type": "u16"
+          },
+          {
+            "name": "payoutStrategy",
+            "type": {
+              "defined": {
+                "name": "payoutStrategy"
+              }
+            }
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "challengeKind",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "expression"
+          },
+          {
+            "name": "textRiddle"
+          }
+        ]
+      }
+    },
+    {
+      "name": "globalConfig",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "nextChallengeId",
+            "type": "u64"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "pendingAuthority",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "pausedOperations",
+            "type": "u8"
+          },
+          {
+            "name": "treasury",
+            "type": "pubkey"
+          },
+          {
+            "name": "protocolFeeBps",
+            "type": "u16"
+          },
+          {
+            "name": "minSetterCutBps",
+            "type": "u16"
+          },
+          {
+            "name": "maxSetterCutBps",
+            "type": "u16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "payoutStrategy",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "equalSplit"
+          },
+          {
+            "name": "winnerTakesAll"
+          },
+          {
+            "name": "topK",
+            "fields": [
+              {
+                "name": "weights",
+                "type": {
+                  "vec": "u16"
+                }
+              }
+            ]
+          },
+          {
+            "name": "lottery",
+            "fields": [
+              {
+                "name": "seedHash",
+                "type": {
+                  "array": [
+                    "u8",
+                    32
+                  ]
+                }
+              }
+            ]
+          }
+        ]
+      }
+    },
+    {
+      "name": "setterClaimed",
+      "type": {
+        "fields": [
+          {
+            "name": "challenge",
+            "type": "pubkey"
+          },
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
+            "name": "setter",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "solutionRevealed",
+      "type": {
+        "fields": [
+          {
+            "name": "challenge",
+            "type": "pubkey"
+          },
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
+            "name": "solution",
+            "type": "string"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "submissionAccount",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
             "name": "submitter",
             "type": "pubkey"
           },
@@ -813,6 +1693,10 @@ export type RiddleRush = {
               ]
             }
           },
+          {
+            "name": "commitmentVersion",
+            "type": "u8"
+          },
           {
             "name": "revealed",
             "type": "bool"
@@ -824,9 +1708,214 @@ export type RiddleRush = {
           {
             "name": "claimed",
             "type": "bool"
+          },
+          {
+            "name": "commitIndex",
+            "type": "u64"
+          },
+          {
+            "name": "correctIndex",
+            "type": "u64"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
           }
         ]
       }
+    },
+    {
+      "name": "submissionClosed",
+      "type": {
+        "fields": [
+          {
+            "name": "challenge",
+            "type": "pubkey"
+          },
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
+            "name": "submission",
+            "type": "pubkey"
+          },
+          {
+            "name": "submitter",
+            "type": "pubkey"
+          },
+          {
+            "name": "lamports",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "submissionCreated",
+      "type": {
+        "fields": [
+          {
+            "name": "challenge",
+            "type": "pubkey"
+          },
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
+            "name": "submission",
+            "type": "pubkey"
+          },
+          {
+            "name": "submitter",
+            "type": "pubkey"
+          },
+          {
+            "name": "encryptedAnswer",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "commitmentVersion",
+            "type": "u8"
+          },
+          {
+            "name": "commitIndex",
+            "type": "u64"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "pot",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "submissionRefunded",
+      "type": {
+        "fields": [
+          {
+            "name": "challenge",
+            "type": "pubkey"
+          },
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
+            "name": "submission",
+            "type": "pubkey"
+          },
+          {
+            "name": "submitter",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "submissionRevealed",
+      "type": {
+        "fields": [
+          {
+            "name": "challenge",
+            "type": "pubkey"
+          },
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
+            "name": "submission",
+            "type": "pubkey"
+          },
+          {
+            "name": "submitter",
+            "type": "pubkey"
+          },
+          {
+            "name": "answer",
+            "type": "string"
+          },
+          {
+            "name": "answerCorrect",
+            "type": "bool"
+          },
+          {
+            "name": "correctSubmissions",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "submitterClaimed",
+      "type": {
+        "fields": [
+          {
+            "name": "challenge",
+            "type": "pubkey"
+          },
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
+            "name": "submission",
+            "type": "pubkey"
+          },
+          {
+            "name": "submitter",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "treasuryClaimed",
+      "type": {
+        "fields": [
+          {
+            "name": "challenge",
+            "type": "pubkey"
+          },
+          {
+            "name": "challengeId",
+            "type": "u64"
+          },
+          {
+            "name": "treasury",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
     }
   ],
   "constants": [
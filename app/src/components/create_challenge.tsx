@@ -41,16 +41,23 @@ const CreateChallenge: React.FC = () => {
         console.log('Existing GlobalConfig found:', globalConfig);
         return true; // Account exists
       } catch (e) {
-        // Account doesn't exist, initialize it
+        // Account doesn't exist, initialize it. Only the upgrade authority may do this
         console.log('GlobalConfig not found, initializing program...');
         try {
+          const [programDataPda] = PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+          );
+          // Same defaults as `riddle-cli init`: no protocol fee and a 10% setter cut
           const tx = await program.methods
-            .initialize()
-            .accounts({
+            .initialize(wallet.publicKey, 0, 1000, 1000)
+            .accountsPartial({
               authority: wallet.publicKey,
-              global_config: globalConfigPda,
-              system_program: SystemProgram.programId,
-            } as any)
+              globalConfig: globalConfigPda,
+              program: program.programId,
+              programData: programDataPda,
+              systemProgram: SystemProgram.programId,
+            })
             .rpc();
           console.log('Program initialized:', tx);
 
@@ -183,21 +190,31 @@ const CreateChallenge: React.FC = () => {
       console.log('Global Config PDA:', globalConfigPda.toBase58());
       console.log('Challenge PDA:', challengePda.toBase58());
 
-      // Create the challenge
-      const tx = await (program.methods as any)
+      // Create the challenge. The app only creates expression challenges, which are solved on-chain,
+      // so there is no solution hash, and pays SOL, so the token accounts are left out
+      const tx = await program.methods
         .createChallenge(
           question,
           new BN(submissionTimestamp),
           new BN(revealTimestamp),
           new BN(claimTimestamp),
-          new BN(entryFeeLamports)
+          new BN(entryFeeLamports),
+          { expression: {} },
+          Array(32).fill(0),
+          { trim: true, lowercase: false, nfkc: false, numeric: true, alternates: [] },
+          globalConfig.maxSetterCutBps,
+          { equalSplit: {} }
         )
-        .accounts({
+        .accountsPartial({
           setter: wallet.publicKey,
-          global_config: globalConfigPda,
-          challenge_account: challengePda,
-          system_program: SystemProgram.programId,
-        } as any)
+          globalConfig: globalConfigPda,
+          challengeAccount: challengePda,
+          systemProgram: SystemProgram.programId,
+          mint: null,
+          vault: null,
+          setterTokenAccount: null,
+          tokenProgram: null,
+        })
         .rpc();
 
       console.log('Transaction signature:', tx);
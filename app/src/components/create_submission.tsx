@@ -5,8 +5,7 @@ import { useWallet } from '@solana/wallet-adapter-react';
 import { Buffer } from 'buffer';
 import BN from 'bn.js';
 import { PublicKey, SystemProgram } from '@solana/web3.js';
-import pkg from 'js-sha3';
-const { keccak_256 } = pkg;
+import { COMMITMENT_VERSION, generateSalt, saveSalt, submissionCommitment } from '../anchor/commitment';
 
 // Polyfill Buffer for browser
 if (typeof window !== 'undefined') {
@@ -24,7 +23,7 @@ const CreateSubmission: React.FC = () => {
 
   const [challenge, setChallenge] = useState<any>(null);
   const [answer, setAnswer] = useState('');
-  const [nonce, setNonce] = useState('');
+  const [salt] = useState<Uint8Array>(() => generateSalt());
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [timeRemaining, setTimeRemaining] = useState<string>('');
@@ -71,16 +70,6 @@ const CreateSubmission: React.FC = () => {
     fetchChallenge();
   }, [program, id, initialChallenge]);
 
-  // Generate random nonce on component mount
-  useEffect(() => {
-    if (!id) return;
-    
-    const randomNonce = Math.random().toString(36).substring(2, 15);
-    setNonce(randomNonce);
-    // Store nonce in cookie
-    document.cookie = `submission_nonce_${id}=${randomNonce}; path=/; max-age=31536000`;
-  }, [id]);
-
   // Calculate time remaining
   useEffect(() => {
     if (!challenge) return;
@@ -160,17 +149,11 @@ const CreateSubmission: React.FC = () => {
         return;
       }
 
-      // Create hash of answer + nonce using Keccak-256
-      const data = `${answer}${nonce}`;
-      
-        // Hash the concatenated string using SHA-256
-      const hashHex = keccak_256(data);
-    
-      // Convert the hash (hex string) to a Uint8Array
-      const hashArray = new Uint8Array(hashHex.match(/.{1,2}/g)!.map(byte => parseInt(byte, 16)));
-      
-      console.log('Submission - Concatenated string:', data);
-      console.log('Submission - Hash array:', hashArray);
+      // Derive the global config PDA
+      const [globalConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("global_config")],
+        program.programId
+      );
 
       // Derive the challenge PDA
       const [challengePda] = PublicKey.findProgramAddressSync(
@@ -184,14 +167,25 @@ const CreateSubmission: React.FC = () => {
         program.programId
       );
 
+      // Commit to the answer, bound to this challenge and wallet, under a random 32-byte salt
+      const commitment = submissionCommitment(challengePda, wallet.publicKey, answer, salt);
+
+      // Save the salt before sending so a committed answer can always be revealed
+      saveSalt(challengePda, wallet.publicKey, salt);
+
       // Create submission
       const tx = await program.methods
-        .createSubmission(Array.from(hashArray))
+        .createSubmission(Array.from(commitment), COMMITMENT_VERSION)
         .accountsPartial({
           submitter: wallet.publicKey,
+          globalConfig: globalConfigPda,
           challengeAccount: challengePda,
           submissionAccount: submissionPda,
           systemProgram: SystemProgram.programId,
+          mint: null,
+          vault: null,
+          submitterTokenAccount: null,
+          tokenProgram: null,
         })
         .rpc();
 
@@ -314,19 +308,19 @@ const CreateSubmission: React.FC = () => {
           </div>
 
           <div style={{ marginBottom: "20px" }}>
-            <label htmlFor="nonce" style={{ 
+            <label htmlFor="salt" style={{ 
               display: "block", 
               marginBottom: "8px",
               fontSize: "16px",
               fontWeight: "500",
               color: "rgba(255, 255, 255, 0.9)",
             }}>
-              Nonce (Save this!):
+              Salt (Save this!):
             </label>
             <input
               type="text"
-              id="nonce"
-              value={nonce}
+              id="salt"
+              value={Buffer.from(salt).toString('hex')}
               readOnly
               style={{
                 width: "100%",
@@ -343,8 +337,8 @@ const CreateSubmission: React.FC = () => {
               color: "rgba(255, 255, 255, 0.7)",
               fontSize: "14px" 
             }}>
-              ⚠️ Important: Save this nonce! You'll need it to reveal your answer later.
-              If you clear your browser cookies, you won't be able to reveal your answer without this nonce.
+              ⚠️ Important: Save this salt! You'll need it to reveal your answer later.
+              If you clear your browser storage, you won't be able to reveal your answer without this salt.
             </p>
           </div>
 
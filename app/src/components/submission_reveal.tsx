@@ -5,7 +5,7 @@ import { useWallet } from '@solana/wallet-adapter-react';
 import { Buffer } from 'buffer';
 import BN from 'bn.js';
 import { PublicKey, SystemProgram } from '@solana/web3.js';
-import { keccak256 } from 'ethereum-cryptography/keccak';
+import { loadSalt, parseSalt, submissionCommitment } from '../anchor/commitment';
 
 // Polyfill Buffer for browser
 if (typeof window !== 'undefined') {
@@ -23,7 +23,7 @@ const SubmissionReveal: React.FC = () => {
 
   const [challenge, setChallenge] = useState<any>(null);
   const [answer, setAnswer] = useState('');
-  const [nonce, setNonce] = useState('');
+  const [salt, setSalt] = useState('');
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [isRevealingSolution, setIsRevealingSolution] = useState(false);
   const [error, setError] = useState<string | null>(null);
@@ -71,19 +71,21 @@ const SubmissionReveal: React.FC = () => {
     fetchChallenge();
   }, [program, id, initialChallenge]);
 
-  // Get nonce from cookie
+  // Get the salt saved when the answer was submitted
   useEffect(() => {
-    if (!id) return;
-    
-    const cookies = document.cookie.split(';');
-    const nonceCookie = cookies.find(cookie => cookie.trim().startsWith(`submission_nonce_${id}=`));
-    if (nonceCookie) {
-      const nonceValue = nonceCookie.split('=')[1];
-      setNonce(nonceValue);
+    if (!program || !wallet.publicKey || !id) return;
+
+    const [challengePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("challenge"), new BN(id).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const savedSalt = loadSalt(challengePda, wallet.publicKey);
+    if (savedSalt) {
+      setSalt(Buffer.from(savedSalt).toString('hex'));
     } else {
-      setError('No nonce found for this challenge. Please make sure you have submitted an answer first.');
+      setError('No salt found for this challenge. Paste the salt you saved when submitting your answer.');
     }
-  }, [id]);
+  }, [program, wallet.publicKey, id]);
 
   // Calculate time remaining
   useEffect(() => {
@@ -165,16 +167,19 @@ const SubmissionReveal: React.FC = () => {
         return;
       }
 
-      // Log the values being sent to the program
-      console.log('Reveal - Nonce:', nonce);
-      console.log('Reveal - Answer:', answer);
-      console.log('Reveal - Concatenated string:', `${answer}${nonce}`);
+      const saltBytes = parseSalt(salt);
+      if (!saltBytes) {
+        setError('The salt must be 64 hex characters');
+        setIsSubmitting(false);
+        return;
+      }
 
-      const concatenated_answer = `${answer}${nonce}`;
-      const hashBuffer = keccak256(Buffer.from(concatenated_answer));
-      const hashArray = Array.from(new Uint8Array(hashBuffer));
+      // Derive the global config PDA
+      const [globalConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("global_config")],
+        program.programId
+      );
 
-      
       // Derive the challenge PDA
       const [challengePda] = PublicKey.findProgramAddressSync(
         [Buffer.from("challenge"), new BN(id).toArrayLike(Buffer, "le", 8)],
@@ -187,15 +192,21 @@ const SubmissionReveal: React.FC = () => {
         program.programId
       );
 
+      // Check the answer and salt against the commitment before paying for a failing transaction
       const submission = await (program.account as any).submissionAccount.fetch(submissionPda);
-      console.log("Submission answer: ", submission.encryptedAnswer);
-      console.log("Hash of answer + nonce: ", hashArray);
+      const commitment = submissionCommitment(challengePda, wallet.publicKey, answer, saltBytes);
+      if (!Buffer.from(commitment).equals(Buffer.from(submission.encryptedAnswer))) {
+        setError('The answer and salt do not match your submission');
+        setIsSubmitting(false);
+        return;
+      }
 
       // Reveal submission
       const tx = await program.methods
-        .submissionSolutionReveal(nonce, answer)
+        .submissionSolutionReveal(Array.from(saltBytes), answer)
         .accountsPartial({
           submitter: wallet.publicKey,
+          globalConfig: globalConfigPda,
           challengeAccount: challengePda,
           submissionAccount: submissionPda,
           systemProgram: SystemProgram.programId,
@@ -237,11 +248,18 @@ const SubmissionReveal: React.FC = () => {
         program.programId
       );
 
-      // Call challenge_solution_reveal instruction
+      const [globalConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("global_config")],
+        program.programId
+      );
+
+      // Call challenge_solution_reveal instruction. The app only creates expression challenges,
+      // which are evaluated on-chain, so no plaintext solution or salt is needed
       const tx = await program.methods
-        .challengeSolutionReveal(new BN(id))
+        .challengeSolutionReveal(new BN(id), '', Array(32).fill(0))
         .accountsPartial({
           user: wallet.publicKey,
+          globalConfig: globalConfigPda,
           challengeAccount: challengePda,
           systemProgram: SystemProgram.programId,
         })
@@ -366,20 +384,21 @@ const SubmissionReveal: React.FC = () => {
           </div>
 
           <div style={{ marginBottom: "20px" }}>
-            <label htmlFor="nonce" style={{ 
+            <label htmlFor="salt" style={{ 
               display: "block", 
               marginBottom: "8px",
               fontSize: "16px",
               fontWeight: "500",
               color: "rgba(255, 255, 255, 0.9)",
             }}>
-              Nonce:
+              Salt:
             </label>
             <input
               type="text"
-              id="nonce"
-              value={nonce}
-              readOnly
+              id="salt"
+              value={salt}
+              onChange={(e) => setSalt(e.target.value)}
+              required
               style={{
                 width: "100%",
                 padding: "12px",
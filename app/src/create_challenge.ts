@@ -43,7 +43,12 @@ async function main() {
                 new BN(submissionDeadline),
                 new BN(answerRevealDeadline),
                 new BN(claimDeadline),
-                new BN(100000000) // 0.1 SOL
+                new BN(100000000), // 0.1 SOL
+                { expression: {} },
+                Array(32).fill(0),
+                { trim: true, lowercase: false, nfkc: false, numeric: true, alternates: [] },
+                1000, // 10% setter cut, the `riddle-cli init` default
+                { equalSplit: {} }
             )
             .accounts({
                 setter: wallet.publicKey,
@@ -0,0 +1,164 @@
+// Human-readable deadlines, amounts and pot shares
+use chrono::{DateTime, Utc};
+use riddle_client::{ChallengeAccount, PayoutStrategy};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Submissions, // Accepting committed answers
+    Reveals,     // Solution and answers are being revealed
+    Claims,      // Setter, winners and treasury can claim
+    Closable,    // The setter may close the challenge and keep the rest
+}
+
+pub fn phase(challenge: &ChallengeAccount, now: i64) -> Phase {
+    if now < challenge.submission_deadline {
+        Phase::Submissions
+    } else if now <= challenge.answer_reveal_deadline {
+        // Reveals are accepted up to and including the deadline, claims only open after it
+        Phase::Reveals
+    } else if now < challenge.claim_deadline {
+        Phase::Claims
+    } else {
+        Phase::Closable
+    }
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Phase::Submissions => "open",
+            Phase::Reveals => "revealing",
+            Phase::Claims => "claiming",
+            Phase::Closable => "closable",
+        })
+    }
+}
+
+// e.g. `2025-04-27 21:08 UTC (in 1d 3h)`
+pub fn deadline(timestamp: i64, now: i64) -> String {
+    let date = DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| timestamp.to_string());
+    if timestamp >= now {
+        format!("{} (in {})", date, duration(timestamp - now))
+    } else {
+        format!("{} ({} ago)", date, duration(now - timestamp))
+    }
+}
+
+fn duration(seconds: i64) -> String {
+    let (days, hours, minutes) = (seconds / 86_400, seconds % 86_400 / 3_600, seconds % 3_600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+// Lamports are shown in SOL, token amounts in base units of the mint
+pub fn amount(challenge: &ChallengeAccount, amount: u64) -> String {
+    match challenge.mint {
+        Some(_) => format!("{} tokens", amount),
+        None => {
+            let sol = format!("{}.{:09}", amount / LAMPORTS_PER_SOL, amount % LAMPORTS_PER_SOL);
+            format!("{} SOL", sol.trim_end_matches('0').trim_end_matches('.'))
+        },
+    }
+}
+
+pub struct Shares {
+    pub setter_cut: u64,
+    pub protocol_fee: u64,
+    // What each eligible submitter can claim, None until someone has submitted or when the share
+    // depends on the winner's rank
+    pub submitter_share: Option<u64>,
+    pub players: u64,
+}
+
+// Mirrors `submitter_claim`: winners share the rest of the pot under the payout strategy, or
+// every revealed answer splits it equally if nobody was right
+pub fn shares(challenge: &ChallengeAccount) -> Shares {
+    let setter_cut = challenge.setter_cut();
+    let protocol_fee = challenge.protocol_fee();
+    let remaining = challenge.pot.saturating_sub(setter_cut).saturating_sub(protocol_fee);
+    let (players, submitter_share) = if challenge.correct_submissions > 0 {
+        let share = match challenge.payout_strategy {
+            PayoutStrategy::EqualSplit => remaining.checked_div(challenge.correct_submissions),
+            _ => None,
+        };
+        (challenge.correct_submissions, share)
+    } else {
+        (challenge.revealed_count, remaining.checked_div(challenge.revealed_count))
+    };
+    Shares {
+        setter_cut,
+        protocol_fee,
+        submitter_share,
+        players,
+    }
+}
+
+pub fn payout_strategy(strategy: &PayoutStrategy) -> String {
+    match strategy {
+        PayoutStrategy::EqualSplit => "equal split".to_string(),
+        PayoutStrategy::WinnerTakesAll => "winner takes all, earliest commit".to_string(),
+        PayoutStrategy::TopK { weights } => {
+            let weights = weights.iter().map(u16::to_string).collect::<Vec<_>>().join(":");
+            format!("top {} by commit order, weighted {}", weights.len(), weights)
+        },
+        PayoutStrategy::Lottery { .. } => "lottery among correct answers".to_string(),
+    }
+}
+
+pub fn print_challenge(challenge: &ChallengeAccount, now: i64) {
+    println!("Challenge #{} [{}]", challenge.id, phase(challenge, now));
+    println!("  question:          {}", challenge.question);
+    println!("  kind:              {:?}", challenge.kind);
+    println!("  setter:            {}", challenge.setter);
+    println!("  entry fee:         {}", amount(challenge, challenge.entry_fee));
+    if let Some(mint) = challenge.mint {
+        println!("  mint:              {}", mint);
+    }
+    println!("  submission until:  {}", deadline(challenge.submission_deadline, now));
+    println!("  reveals until:     {}", deadline(challenge.answer_reveal_deadline, now));
+    println!("  claims until:      {}", deadline(challenge.claim_deadline, now));
+    if !challenge.solution.is_empty() {
+        println!("  solution:          {}", challenge.solution);
+    }
+    println!(
+        "  submissions:       {} ({} revealed, {} correct)",
+        challenge.submission_count, challenge.revealed_count, challenge.correct_submissions
+    );
+
+    let shares = shares(challenge);
+    println!("  pot:               {}", amount(challenge, challenge.pot));
+    println!("  payout:            {}", payout_strategy(&challenge.payout_strategy));
+    println!(
+        "  paid out:          {} ({} claims)",
+        amount(challenge, challenge.total_paid_out),
+        challenge.claims_count
+    );
+    println!(
+        "  setter cut:        {} ({} bps)",
+        amount(challenge, shares.setter_cut),
+        challenge.setter_cut_bps
+    );
+    println!(
+        "  protocol fee:      {} ({} bps)",
+        amount(challenge, shares.protocol_fee),
+        challenge.protocol_fee_bps
+    );
+    if challenge.solution.is_empty() && now > challenge.answer_reveal_deadline {
+        println!("  unresolved:        no solution was revealed, every player can be refunded their entry fee");
+    } else if let Some(share) = shares.submitter_share {
+        let who = if challenge.correct_submissions > 0 { "winner" } else { "player" };
+        let label = format!("share per {}:", who);
+        println!("  {:<19}{} ({} eligible)", label, amount(challenge, share), shares.players);
+    }
+}
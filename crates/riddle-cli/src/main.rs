@@ -0,0 +1,572 @@
+// Command-line tool for Riddle Rush setters and players
+mod display;
+mod rpc;
+
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand};
+use riddle_client::{
+    answer_commitment, challenge_address, decode_challenge, decode_global_config, decode_submission, derive_nonce,
+    global_config_address, lottery_seed_commitment, nonce_message, solution_commitment, submission_address, to_hex,
+    AnswerNormalization, ChallengeAccount, ChallengeKind, CreateChallengeArgs, FeeConfig, GlobalConfig, NonceVault,
+    PayoutStrategy, SecretKind, TokenAccounts, VaultEntry,
+};
+use serde::Deserialize;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+
+use crate::display::Phase;
+use crate::rpc::RpcClient;
+
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = anchor_lang::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+#[derive(Parser)]
+#[command(name = "riddle-rush", about = "Create, play and settle Riddle Rush challenges")]
+struct Args {
+    /// JSON-RPC endpoint, a local test validator by default
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Signer and fee payer, the Solana CLI's default keypair when not set
+    #[arg(long, global = true)]
+    keypair: Option<PathBuf>,
+    /// Encrypted vault keeping answers, nonces and text riddle salts until they are revealed.
+    /// Its password is read from RIDDLE_RUSH_VAULT_PASSWORD or prompted for
+    #[arg(long, global = true)]
+    vault: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the global config, signed by the program's upgrade authority
+    Init {
+        /// Receives the protocol fee, the signer when not set
+        #[arg(long)]
+        treasury: Option<Pubkey>,
+        #[arg(long, default_value_t = 0)]
+        protocol_fee_bps: u16,
+        #[arg(long, default_value_t = 1_000)]
+        min_setter_cut_bps: u16,
+        #[arg(long, default_value_t = 1_000)]
+        max_setter_cut_bps: u16,
+    },
+    /// Create a challenge described by a JSON file such as `create_challenge.json`
+    Create { file: PathBuf },
+    /// List challenges still accepting submissions
+    List {
+        /// Include challenges past their submission deadline
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show a challenge with its deadlines and pot shares
+    Show { id: u64 },
+    /// Commit an answer, keeping it and its nonce in the vault for the reveal
+    Submit { id: u64, answer: String },
+    /// Reveal the challenge's solution, text riddles need the setter's vault or wallet
+    RevealSolution {
+        id: u64,
+        /// Text riddle solution to reveal when it is missing from the vault
+        #[arg(long)]
+        solution: Option<String>,
+    },
+    /// Reveal your committed answer from the vault
+    Reveal {
+        id: u64,
+        /// Answer to reveal when it is missing from the vault, its nonce is derived from the wallet
+        #[arg(long)]
+        answer: Option<String>,
+    },
+    /// List the answers and nonces kept in the vault
+    Vault,
+    /// Claim your prize or refund, or the setter's cut or protocol fee
+    Claim {
+        id: u64,
+        /// Claim the setter's cut instead of a submitter's share
+        #[arg(long, conflicts_with = "treasury")]
+        setter: bool,
+        /// Sends the protocol fee to the configured treasury, anyone may pay for it
+        #[arg(long)]
+        treasury: bool,
+        /// Token account receiving the payout, the associated token account by default
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+    },
+    /// Close a challenge after its claim deadline, returning what is left to the setter
+    Close {
+        id: u64,
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+    },
+    /// Refund an entry fee when the solution was never revealed, anyone may pay for it
+    Refund {
+        id: u64,
+        /// Player to refund, yourself by default
+        #[arg(long)]
+        submitter: Option<Pubkey>,
+        /// The player's token account, their associated token account by default
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+    },
+    /// Close your submission once it can't claim anything, recovering its rent
+    CloseSubmission {
+        id: u64,
+        /// Close another player's submission after the claim deadline, the rent goes back to them
+        #[arg(long)]
+        submitter: Option<Pubkey>,
+    },
+}
+
+// Shape of the file given to `create`, only the fields of `create_challenge.json` are required
+#[derive(Deserialize)]
+struct ChallengeFile {
+    question: String,
+    submission_deadline: i64,
+    answer_reveal_deadline: i64,
+    claim_deadline: i64,
+    entry_fee: u64,
+    #[serde(default)]
+    kind: KindFile,
+    // Solution of a text riddle, kept in the vault and only its hash is sent
+    solution: Option<String>,
+    #[serde(default)]
+    normalization: NormalizationFile,
+    // Defaults to the highest cut the config allows
+    setter_cut_bps: Option<u16>,
+    // e.g. `{ "strategy": "top_k", "weights": [5, 3, 2] }`, an equal split when not set
+    #[serde(default)]
+    payout: PayoutFile,
+    // Entry fee token, lamports when not set
+    mint: Option<String>,
+    token_account: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum KindFile {
+    #[default]
+    Expression,
+    TextRiddle,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+enum PayoutFile {
+    #[default]
+    EqualSplit,
+    WinnerTakesAll,
+    TopK { weights: Vec<u16> },
+    Lottery,
+}
+
+#[derive(Deserialize, Default)]
+struct NormalizationFile {
+    #[serde(default)]
+    trim: bool,
+    #[serde(default)]
+    lowercase: bool,
+    #[serde(default)]
+    nfkc: bool,
+    #[serde(default)]
+    numeric: bool,
+    #[serde(default)]
+    alternates: Vec<String>,
+}
+
+struct Cli {
+    rpc: RpcClient,
+    payer: Keypair,
+    vault_path: PathBuf,
+}
+
+impl Cli {
+    fn global_config(&self) -> Result<GlobalConfig> {
+        let account = self
+            .rpc
+            .account(&global_config_address())?
+            .ok_or_else(|| anyhow!("the global config is not initialized, run `riddle-rush init`"))?;
+        decode_global_config(&account.data).map_err(|e| anyhow!("invalid global config: {}", e))
+    }
+
+    fn challenge(&self, id: u64) -> Result<ChallengeAccount> {
+        let account = self
+            .rpc
+            .account(&challenge_address(id))?
+            .ok_or_else(|| anyhow!("challenge #{} does not exist", id))?;
+        decode_challenge(&account.data).map_err(|e| anyhow!("invalid challenge #{}: {}", id, e))
+    }
+
+    // Token accounts for the payer's side of a transfer in `mint`, None for lamport challenges
+    fn token_accounts(&self, mint: Option<Pubkey>, token_account: Option<Pubkey>) -> Result<Option<TokenAccounts>> {
+        self.token_accounts_of(&self.payer.pubkey(), mint, token_account)
+    }
+
+    // Token accounts for `owner`'s side of a transfer, its associated token account unless given
+    fn token_accounts_of(&self, owner: &Pubkey, mint: Option<Pubkey>, token_account: Option<Pubkey>) -> Result<Option<TokenAccounts>> {
+        let Some(mint) = mint else {
+            return Ok(None);
+        };
+        let token_program = self
+            .rpc
+            .account(&mint)?
+            .ok_or_else(|| anyhow!("mint {} does not exist", mint))?
+            .owner;
+        let user_token_account = token_account.unwrap_or_else(|| {
+            Pubkey::find_program_address(
+                &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+                &ASSOCIATED_TOKEN_PROGRAM_ID,
+            )
+            .0
+        });
+        Ok(Some(TokenAccounts {
+            mint,
+            user_token_account,
+            token_program,
+        }))
+    }
+
+    fn send(&self, instruction: solana_sdk::instruction::Instruction) -> Result<()> {
+        let signature = self.rpc.send(&self.payer, &[instruction])?;
+        println!("Signature: {}", signature);
+        Ok(())
+    }
+
+    fn init(&self, fees: FeeConfig) -> Result<()> {
+        println!("Initializing the global config at {}", global_config_address());
+        self.send(riddle_client::initialize(&self.payer.pubkey(), &fees))
+    }
+
+    fn create(&self, file: &PathBuf) -> Result<()> {
+        let contents = fs::read_to_string(file).with_context(|| format!("failed to read {}", file.display()))?;
+        let spec: ChallengeFile = serde_json::from_str(&contents).with_context(|| format!("invalid {}", file.display()))?;
+        let config = self.global_config()?;
+        let id = config.next_challenge_id;
+        let challenge = challenge_address(id);
+
+        let (kind, solution_hash) = match spec.kind {
+            KindFile::Expression => {
+                // Evaluated exactly as the program will, so a bad question fails before paying for it
+                let answer = riddle_expression::evaluate_expression(&spec.question)
+                    .map_err(|e| anyhow!("invalid question: {}", e))?;
+                println!("Answer: {}", answer);
+                (ChallengeKind::Expression, [0u8; 32])
+            },
+            KindFile::TextRiddle => {
+                let solution = spec.solution.ok_or_else(|| anyhow!("a text riddle needs a `solution`"))?;
+                if !spec.normalization.alternates.is_empty() {
+                    bail!("text riddles can't have `alternates`, they would be public before the solution is revealed");
+                }
+                let salt = self.nonce(&challenge);
+                let hash = solution_commitment(&challenge, &solution, &salt);
+                self.vault()?.insert(VaultEntry {
+                    kind: SecretKind::Solution,
+                    challenge_id: id,
+                    challenge,
+                    owner: self.payer.pubkey(),
+                    answer: solution,
+                    nonce: salt,
+                })?;
+                (ChallengeKind::TextRiddle, hash)
+            },
+        };
+        let mint = spec.mint.as_deref().map(Pubkey::from_str).transpose()?;
+        let token_account = spec.token_account.as_deref().map(Pubkey::from_str).transpose()?;
+        let args = CreateChallengeArgs {
+            question: spec.question,
+            submission_deadline: spec.submission_deadline,
+            answer_reveal_deadline: spec.answer_reveal_deadline,
+            claim_deadline: spec.claim_deadline,
+            entry_fee: spec.entry_fee,
+            kind,
+            solution_hash,
+            normalization: AnswerNormalization {
+                trim: spec.normalization.trim,
+                lowercase: spec.normalization.lowercase,
+                nfkc: spec.normalization.nfkc,
+                numeric: spec.normalization.numeric,
+                alternates: spec.normalization.alternates,
+            },
+            setter_cut_bps: spec.setter_cut_bps.unwrap_or(config.max_setter_cut_bps),
+            payout_strategy: match spec.payout {
+                PayoutFile::EqualSplit => PayoutStrategy::EqualSplit,
+                PayoutFile::WinnerTakesAll => PayoutStrategy::WinnerTakesAll,
+                PayoutFile::TopK { weights } => PayoutStrategy::TopK { weights },
+                // The seed is the setter's derived nonce, like the salt of a text riddle
+                PayoutFile::Lottery => PayoutStrategy::Lottery {
+                    seed_hash: lottery_seed_commitment(&challenge, &self.nonce(&challenge)),
+                },
+            },
+        };
+        let tokens = self.token_accounts(mint, token_account)?;
+
+        println!("Creating challenge #{} at {}", id, challenge);
+        self.send(riddle_client::create_challenge(&self.payer.pubkey(), id, args, tokens))?;
+        display::print_challenge(&self.challenge(id)?, now());
+        Ok(())
+    }
+
+    fn list(&self, all: bool) -> Result<()> {
+        let now = now();
+        let mut challenges: Vec<ChallengeAccount> = self
+            .rpc
+            .program_accounts(&ChallengeAccount::DISCRIMINATOR)?
+            .into_iter()
+            .filter_map(|(_, account)| decode_challenge(&account.data).ok())
+            .filter(|challenge| all || display::phase(challenge, now) == Phase::Submissions)
+            .collect();
+        challenges.sort_by_key(|challenge| challenge.id);
+        if challenges.is_empty() {
+            println!("No {}challenges", if all { "" } else { "open " });
+        }
+        for challenge in &challenges {
+            println!(
+                "#{:<5} {:<10} fee {:<16} pot {:<16} {:>3} players  submit until {}",
+                challenge.id,
+                display::phase(challenge, now).to_string(),
+                display::amount(challenge, challenge.entry_fee),
+                display::amount(challenge, challenge.pot),
+                challenge.submission_count,
+                display::deadline(challenge.submission_deadline, now),
+            );
+            println!("       {}", challenge.question);
+        }
+        Ok(())
+    }
+
+    fn submit(&self, id: u64, answer: String) -> Result<()> {
+        let challenge = self.challenge(id)?;
+        let nonce = self.nonce(&challenge_address(id));
+        let commitment = answer_commitment(&challenge_address(id), &self.payer.pubkey(), &answer, &nonce);
+        self.vault()?.insert(VaultEntry {
+            kind: SecretKind::Submission,
+            challenge_id: id,
+            challenge: challenge_address(id),
+            owner: self.payer.pubkey(),
+            answer,
+            nonce,
+        })?;
+        let tokens = self.token_accounts(challenge.mint, None)?;
+
+        println!("Submitting to challenge #{} for {}", id, display::amount(&challenge, challenge.entry_fee));
+        self.send(riddle_client::create_submission(&self.payer.pubkey(), id, commitment, tokens))?;
+        println!(
+            "Reveal with `riddle-rush reveal {}` before {}",
+            id,
+            display::deadline(challenge.answer_reveal_deadline, now())
+        );
+        Ok(())
+    }
+
+    fn reveal_solution(&self, id: u64, solution: Option<String>) -> Result<()> {
+        let challenge = self.challenge(id)?;
+        let (solution, salt) = match challenge.kind {
+            // A lottery's seed is the setter's derived nonce, other expressions need no salt
+            ChallengeKind::Expression => match challenge.payout_strategy {
+                PayoutStrategy::Lottery { .. } => (String::new(), self.nonce(&challenge_address(id))),
+                _ => (String::new(), [0u8; 32]),
+            },
+            ChallengeKind::TextRiddle => {
+                // The setter's salt is their derived nonce
+                let (solution, salt) = self.secret(SecretKind::Solution, id, solution)?;
+                if solution_commitment(&challenge_address(id), &solution, &salt) != challenge.solution_hash {
+                    bail!("the solution doesn't match the hash committed by challenge #{}", id);
+                }
+                (solution, salt)
+            },
+        };
+        self.send(riddle_client::challenge_solution_reveal(&self.payer.pubkey(), id, solution, salt))?;
+        println!("Solution: {}", self.challenge(id)?.solution);
+        Ok(())
+    }
+
+    fn reveal(&self, id: u64, answer: Option<String>) -> Result<()> {
+        let (answer, nonce) = self.secret(SecretKind::Submission, id, answer)?;
+        let commitment = answer_commitment(&challenge_address(id), &self.payer.pubkey(), &answer, &nonce);
+        if commitment != self.submission(id)?.encrypted_answer {
+            bail!("the answer doesn't match your commitment to challenge #{}", id);
+        }
+        self.send(riddle_client::submission_solution_reveal(
+            &self.payer.pubkey(),
+            id,
+            nonce,
+            answer.clone(),
+        ))?;
+
+        let submission = self.submission(id)?;
+        println!("Your answer {:?} is {}", answer, if submission.answer_correct { "correct" } else { "wrong" });
+        Ok(())
+    }
+
+    // Nonce of the payer's commitments to a challenge, recoverable from the wallet alone
+    fn nonce(&self, challenge: &Pubkey) -> [u8; 32] {
+        derive_nonce(self.payer.sign_message(&nonce_message(challenge)).as_ref())
+    }
+
+    fn vault(&self) -> Result<NonceVault> {
+        let password = match std::env::var("RIDDLE_RUSH_VAULT_PASSWORD") {
+            Ok(password) => password,
+            Err(_) => rpassword::prompt_password(format!("Password for {}: ", self.vault_path.display()))?,
+        };
+        NonceVault::open(&self.vault_path, &password).with_context(|| format!("failed to open {}", self.vault_path.display()))
+    }
+
+    fn list_vault(&self) -> Result<()> {
+        for entry in self.vault()?.entries() {
+            let kind = match entry.kind {
+                SecretKind::Submission => "answer",
+                SecretKind::Solution => "solution",
+            };
+            println!("#{:<5} {:<8} {:?} nonce {} ({})", entry.challenge_id, kind, entry.answer, to_hex(&entry.nonce), entry.owner);
+        }
+        Ok(())
+    }
+
+    // Answer and nonce committed by the payer, from the vault or else the given answer and the
+    // nonce derived from the wallet
+    fn secret(&self, kind: SecretKind, id: u64, answer: Option<String>) -> Result<(String, [u8; 32])> {
+        let challenge = challenge_address(id);
+        if let Some(answer) = answer {
+            return Ok((answer, self.nonce(&challenge)));
+        }
+        let vault = self.vault()?;
+        let entry = vault.get(kind, &challenge, &self.payer.pubkey()).ok_or_else(|| {
+            anyhow!("challenge #{} is not in the vault, pass the committed answer to recover its nonce", id)
+        })?;
+        Ok((entry.answer.clone(), entry.nonce))
+    }
+
+    fn submission(&self, id: u64) -> Result<riddle_client::SubmissionAccount> {
+        let address = submission_address(&challenge_address(id), &self.payer.pubkey());
+        let account = self
+            .rpc
+            .account(&address)?
+            .ok_or_else(|| anyhow!("no submission to challenge #{} from {}", id, self.payer.pubkey()))?;
+        decode_submission(&account.data).map_err(|e| anyhow!("invalid submission: {}", e))
+    }
+
+    fn claim(&self, id: u64, setter: bool, treasury: bool, token_account: Option<Pubkey>) -> Result<()> {
+        let challenge = self.challenge(id)?;
+        let shares = display::shares(&challenge);
+        let instruction = if setter {
+            let tokens = self.token_accounts(challenge.mint, token_account)?;
+            println!("Claiming the setter cut of {}", display::amount(&challenge, shares.setter_cut));
+            riddle_client::setter_claim(&self.payer.pubkey(), id, tokens)
+        } else if treasury {
+            let config = self.global_config()?;
+            let tokens = match challenge.mint {
+                // The fee goes to the treasury's token account, not the payer's
+                Some(_) => {
+                    let owner_account = token_account.ok_or_else(|| anyhow!("--token-account of the treasury is required"))?;
+                    self.token_accounts(challenge.mint, Some(owner_account))?
+                },
+                None => None,
+            };
+            println!("Sending the protocol fee of {} to {}", display::amount(&challenge, shares.protocol_fee), config.treasury);
+            riddle_client::treasury_claim(&self.payer.pubkey(), &config.treasury, id, tokens)
+        } else {
+            let tokens = self.token_accounts(challenge.mint, token_account)?;
+            let submission = self.submission(id)?;
+            // The last claim also takes the rounding remainder of the shares
+            let share = challenge
+                .submitter_share(&submission)
+                .map_err(|_| anyhow!("your answer doesn't win a share of challenge #{}", id))?;
+            println!("Claiming {}", display::amount(&challenge, share));
+            riddle_client::submitter_claim(&self.payer.pubkey(), id, tokens)
+        };
+        self.send(instruction)
+    }
+
+    fn close(&self, id: u64, token_account: Option<Pubkey>) -> Result<()> {
+        let challenge = self.challenge(id)?;
+        if display::phase(&challenge, now()) != Phase::Closable {
+            bail!(
+                "challenge #{} can only be closed after {}",
+                id,
+                display::deadline(challenge.claim_deadline, now())
+            );
+        }
+        let tokens = self.token_accounts(challenge.mint, token_account)?;
+        self.send(riddle_client::setter_close_challenge(&self.payer.pubkey(), id, tokens))
+    }
+
+    fn refund(&self, id: u64, submitter: Option<Pubkey>, token_account: Option<Pubkey>) -> Result<()> {
+        let challenge = self.challenge(id)?;
+        if !challenge.solution.is_empty() {
+            bail!("challenge #{} was resolved, use `claim` instead", id);
+        }
+        let submitter = submitter.unwrap_or_else(|| self.payer.pubkey());
+        let tokens = self.token_accounts_of(&submitter, challenge.mint, token_account)?;
+        println!("Refunding {}'s entry fee to challenge #{}", submitter, id);
+        self.send(riddle_client::refund(&self.payer.pubkey(), id, &submitter, tokens))
+    }
+
+    fn close_submission(&self, id: u64, submitter: Option<Pubkey>) -> Result<()> {
+        match submitter {
+            Some(submitter) if submitter != self.payer.pubkey() => {
+                println!("Returning the rent of {}'s submission to challenge #{}", submitter, id);
+                self.send(riddle_client::sweep_submission(&self.payer.pubkey(), id, &submitter))
+            },
+            _ => {
+                self.submission(id)?;
+                self.send(riddle_client::close_submission(&self.payer.pubkey(), id))
+            },
+        }
+    }
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let keypair_path = args
+        .keypair
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".config/solana/id.json"));
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|e| anyhow!("failed to read keypair {}: {}", keypair_path.display(), e))?;
+    let cli = Cli {
+        rpc: RpcClient::new(&args.url),
+        payer,
+        vault_path: args
+            .vault
+            .unwrap_or_else(|| dirs::config_dir().unwrap_or_default().join("riddle-rush").join("vault.json")),
+    };
+
+    match args.command {
+        Command::Init {
+            treasury,
+            protocol_fee_bps,
+            min_setter_cut_bps,
+            max_setter_cut_bps,
+        } => cli.init(FeeConfig {
+            treasury: treasury.unwrap_or_else(|| cli.payer.pubkey()),
+            protocol_fee_bps,
+            min_setter_cut_bps,
+            max_setter_cut_bps,
+        }),
+        Command::Create { file } => cli.create(&file),
+        Command::List { all } => cli.list(all),
+        Command::Show { id } => {
+            display::print_challenge(&cli.challenge(id)?, now());
+            Ok(())
+        },
+        Command::Submit { id, answer } => cli.submit(id, answer),
+        Command::RevealSolution { id, solution } => cli.reveal_solution(id, solution),
+        Command::Reveal { id, answer } => cli.reveal(id, answer),
+        Command::Vault => cli.list_vault(),
+        Command::Claim {
+            id,
+            setter,
+            treasury,
+            token_account,
+        } => cli.claim(id, setter, treasury, token_account),
+        Command::Close { id, token_account } => cli.close(id, token_account),
+        Command::Refund { id, submitter, token_account } => cli.refund(id, submitter, token_account),
+        Command::CloseSubmission { id, submitter } => cli.close_submission(id, submitter),
+    }
+}
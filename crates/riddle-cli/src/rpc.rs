@@ -0,0 +1,132 @@
+// Minimal JSON-RPC client: the handful of calls the CLI needs to read accounts and land transactions
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct Account {
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        RpcClient {
+            url: url.to_string(),
+            agent: ureq::agent(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .with_context(|| format!("{} request to {} failed", method, self.url))?
+            .into_json()?;
+        if let Some(error) = response.get("error") {
+            // Simulation failures carry the program logs, which hold the Anchor error
+            let logs = error["data"]["logs"]
+                .as_array()
+                .map(|logs| logs.iter().filter_map(Value::as_str).collect::<Vec<_>>().join("\n"))
+                .unwrap_or_default();
+            bail!("{} failed: {}\n{}", method, error["message"].as_str().unwrap_or_default(), logs);
+        }
+        Ok(response["result"].clone())
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        parse_account(&result["value"])
+    }
+
+    // Program accounts whose data starts with the Anchor discriminator
+    pub fn program_accounts(&self, discriminator: &[u8]) -> Result<Vec<(Pubkey, Account)>> {
+        let filter = json!({ "memcmp": { "offset": 0, "bytes": BASE64.encode(discriminator), "encoding": "base64" } });
+        let result = self.call(
+            "getProgramAccounts",
+            json!([
+                riddle_rush::ID.to_string(),
+                { "encoding": "base64", "commitment": "confirmed", "filters": [filter] }
+            ]),
+        )?;
+        let mut accounts = Vec::new();
+        for entry in result.as_array().map(Vec::as_slice).unwrap_or_default() {
+            let address = Pubkey::from_str(entry["pubkey"].as_str().unwrap_or_default())?;
+            if let Some(account) = parse_account(&entry["account"])? {
+                accounts.push((address, account));
+            }
+        }
+        Ok(accounts)
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"].as_str().ok_or_else(|| anyhow!("no blockhash in {}", result))?;
+        Ok(Hash::from_str(blockhash)?)
+    }
+
+    // Signs with the payer and waits until the transaction is confirmed
+    pub fn send(&self, payer: &Keypair, instructions: &[Instruction]) -> Result<Signature> {
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &[payer],
+            self.latest_blockhash()?,
+        );
+        let encoded = BASE64.encode(bincode::serialize(&transaction)?);
+        let result = self.call(
+            "sendTransaction",
+            json!([encoded, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature = Signature::from_str(result.as_str().ok_or_else(|| anyhow!("no signature in {}", result))?)?;
+
+        let started = Instant::now();
+        while started.elapsed() < CONFIRM_TIMEOUT {
+            let result = self.call("getSignatureStatuses", json!([[signature.to_string()]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    bail!("transaction {} failed: {}", signature, status["err"]);
+                }
+                if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                    return Ok(signature);
+                }
+            }
+            sleep(CONFIRM_POLL_INTERVAL);
+        }
+        bail!("transaction {} was not confirmed in {:?}", signature, CONFIRM_TIMEOUT)
+    }
+}
+
+fn parse_account(value: &Value) -> Result<Option<Account>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let data = value["data"][0].as_str().ok_or_else(|| anyhow!("no account data in {}", value))?;
+    Ok(Some(Account {
+        owner: Pubkey::from_str(value["owner"].as_str().unwrap_or_default())?,
+        data: BASE64.decode(data)?,
+    }))
+}
@@ -0,0 +1,155 @@
+// Decodes program events from transaction logs and program accounts from their data
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use riddle_rush::{
+    ChallengeAccount, ChallengeClosed, ChallengeCreated, GlobalConfig, SetterClaimed, SolutionRevealed, SubmissionAccount,
+    SubmissionClosed, SubmissionCreated, SubmissionRefunded, SubmissionRevealed, SubmitterClaimed, TreasuryClaimed,
+};
+
+pub enum ProgramEvent {
+    ChallengeCreated(ChallengeCreated),
+    SubmissionCreated(SubmissionCreated),
+    SolutionRevealed(SolutionRevealed),
+    SubmissionRevealed(SubmissionRevealed),
+    SetterClaimed(SetterClaimed),
+    SubmitterClaimed(SubmitterClaimed),
+    TreasuryClaimed(TreasuryClaimed),
+    ChallengeClosed(ChallengeClosed),
+    SubmissionClosed(SubmissionClosed),
+    SubmissionRefunded(SubmissionRefunded),
+}
+
+impl ProgramEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProgramEvent::ChallengeCreated(_) => "ChallengeCreated",
+            ProgramEvent::SubmissionCreated(_) => "SubmissionCreated",
+            ProgramEvent::SolutionRevealed(_) => "SolutionRevealed",
+            ProgramEvent::SubmissionRevealed(_) => "SubmissionRevealed",
+            ProgramEvent::SetterClaimed(_) => "SetterClaimed",
+            ProgramEvent::SubmitterClaimed(_) => "SubmitterClaimed",
+            ProgramEvent::TreasuryClaimed(_) => "TreasuryClaimed",
+            ProgramEvent::ChallengeClosed(_) => "ChallengeClosed",
+            ProgramEvent::SubmissionClosed(_) => "SubmissionClosed",
+            ProgramEvent::SubmissionRefunded(_) => "SubmissionRefunded",
+        }
+    }
+
+    pub fn challenge(&self) -> Pubkey {
+        match self {
+            ProgramEvent::ChallengeCreated(e) => e.challenge,
+            ProgramEvent::SubmissionCreated(e) => e.challenge,
+            ProgramEvent::SolutionRevealed(e) => e.challenge,
+            ProgramEvent::SubmissionRevealed(e) => e.challenge,
+            ProgramEvent::SetterClaimed(e) => e.challenge,
+            ProgramEvent::SubmitterClaimed(e) => e.challenge,
+            ProgramEvent::TreasuryClaimed(e) => e.challenge,
+            ProgramEvent::ChallengeClosed(e) => e.challenge,
+            ProgramEvent::SubmissionClosed(e) => e.challenge,
+            ProgramEvent::SubmissionRefunded(e) => e.challenge,
+        }
+    }
+
+    pub fn submission(&self) -> Option<Pubkey> {
+        match self {
+            ProgramEvent::SubmissionCreated(e) => Some(e.submission),
+            ProgramEvent::SubmissionRevealed(e) => Some(e.submission),
+            ProgramEvent::SubmitterClaimed(e) => Some(e.submission),
+            ProgramEvent::SubmissionClosed(e) => Some(e.submission),
+            ProgramEvent::SubmissionRefunded(e) => Some(e.submission),
+            _ => None,
+        }
+    }
+}
+
+// Decodes the payload of a `Program data:` log line, discriminator included
+pub fn decode_event(data: &[u8]) -> Option<ProgramEvent> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (discriminator, mut payload) = data.split_at(8);
+    macro_rules! decode {
+        ($($event:ident),*) => {
+            $(if discriminator == $event::DISCRIMINATOR {
+                return $event::deserialize(&mut payload).ok().map(ProgramEvent::$event);
+            })*
+        };
+    }
+    decode!(
+        ChallengeCreated,
+        SubmissionCreated,
+        SolutionRevealed,
+        SubmissionRevealed,
+        SetterClaimed,
+        SubmitterClaimed,
+        TreasuryClaimed,
+        ChallengeClosed,
+        SubmissionClosed,
+        SubmissionRefunded
+    );
+    None
+}
+
+// Raw event payloads emitted by the program in a transaction, in emission order. Logs of other
+// programs, including ones the program invokes, are skipped
+pub fn event_data(logs: &[String], program_id: &Pubkey) -> Vec<Vec<u8>> {
+    let program_id = program_id.to_string();
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+    for log in logs {
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if invocations.last() == Some(&program_id.as_str()) {
+                if let Ok(data) = BASE64.decode(data.trim()) {
+                    events.push(data);
+                }
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => invocations.push(program),
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    invocations.pop();
+                },
+                _ => {},
+            }
+        }
+    }
+    events
+}
+
+pub enum ProgramAccount {
+    Challenge(Box<ChallengeAccount>),
+    Submission(SubmissionAccount),
+    GlobalConfig(GlobalConfig),
+}
+
+pub fn decode_account(data: &[u8]) -> Option<ProgramAccount> {
+    if data.len() < 8 {
+        return None;
+    }
+    let discriminator = &data[..8];
+    let mut data = data;
+    if discriminator == ChallengeAccount::DISCRIMINATOR {
+        ChallengeAccount::try_deserialize(&mut data).ok().map(|challenge| ProgramAccount::Challenge(Box::new(challenge)))
+    } else if discriminator == SubmissionAccount::DISCRIMINATOR {
+        SubmissionAccount::try_deserialize(&mut data).ok().map(ProgramAccount::Submission)
+    } else if discriminator == GlobalConfig::DISCRIMINATOR {
+        GlobalConfig::try_deserialize(&mut data).ok().map(ProgramAccount::GlobalConfig)
+    } else {
+        None
+    }
+}
+
+pub fn decode_challenge(mut data: &[u8]) -> anchor_lang::Result<ChallengeAccount> {
+    ChallengeAccount::try_deserialize(&mut data)
+}
+
+pub fn decode_submission(mut data: &[u8]) -> anchor_lang::Result<SubmissionAccount> {
+    SubmissionAccount::try_deserialize(&mut data)
+}
+
+pub fn decode_global_config(mut data: &[u8]) -> anchor_lang::Result<GlobalConfig> {
+    GlobalConfig::try_deserialize(&mut data)
+}
@@ -0,0 +1,346 @@
+// Builders for every program entrypoint, deriving the PDAs each one expects
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use riddle_rush::{accounts, instruction, AnswerNormalization, ChallengeKind, PayoutStrategy};
+
+use crate::pda::{challenge_address, global_config_address, program_data_address, submission_address, vault_address};
+
+// Token accounts for challenges whose entry fee is an SPL token. The vault is derived
+// from the challenge, so only the caller's side of the transfer is needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenAccounts {
+    pub mint: Pubkey,
+    pub user_token_account: Pubkey,
+    pub token_program: Pubkey,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeConfig {
+    pub treasury: Pubkey,
+    pub protocol_fee_bps: u16,
+    pub min_setter_cut_bps: u16,
+    pub max_setter_cut_bps: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateChallengeArgs {
+    pub question: String,
+    pub submission_deadline: i64,
+    pub answer_reveal_deadline: i64,
+    pub claim_deadline: i64,
+    pub entry_fee: u64,
+    pub kind: ChallengeKind,
+    pub solution_hash: [u8; 32],
+    pub normalization: AnswerNormalization,
+    pub setter_cut_bps: u16,
+    pub payout_strategy: PayoutStrategy,
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: riddle_rush::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// Splits optional token accounts into the optional fields of the accounts structs
+fn token_fields(challenge: &Pubkey, tokens: Option<TokenAccounts>) -> [Option<Pubkey>; 4] {
+    match tokens {
+        Some(t) => [
+            Some(t.mint),
+            Some(vault_address(challenge)),
+            Some(t.user_token_account),
+            Some(t.token_program),
+        ],
+        None => [None; 4],
+    }
+}
+
+pub fn initialize(authority: &Pubkey, fees: &FeeConfig) -> Instruction {
+    build(
+        accounts::Initialize {
+            authority: *authority,
+            global_config: global_config_address(),
+            program: riddle_rush::ID,
+            program_data: program_data_address(),
+            system_program: system_program::ID,
+        },
+        instruction::Initialize {
+            treasury: fees.treasury,
+            protocol_fee_bps: fees.protocol_fee_bps,
+            min_setter_cut_bps: fees.min_setter_cut_bps,
+            max_setter_cut_bps: fees.max_setter_cut_bps,
+        },
+    )
+}
+
+pub fn update_config(authority: &Pubkey, fees: &FeeConfig) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            authority: *authority,
+            global_config: global_config_address(),
+        },
+        instruction::UpdateConfig {
+            treasury: fees.treasury,
+            protocol_fee_bps: fees.protocol_fee_bps,
+            min_setter_cut_bps: fees.min_setter_cut_bps,
+            max_setter_cut_bps: fees.max_setter_cut_bps,
+        },
+    )
+}
+
+pub fn propose_authority(authority: &Pubkey, new_authority: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::ProposeAuthority {
+            authority: *authority,
+            global_config: global_config_address(),
+        },
+        instruction::ProposeAuthority { new_authority },
+    )
+}
+
+pub fn accept_authority(pending_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAuthority {
+            pending_authority: *pending_authority,
+            global_config: global_config_address(),
+        },
+        instruction::AcceptAuthority {},
+    )
+}
+
+pub fn set_paused(authority: &Pubkey, paused_operations: u8) -> Instruction {
+    build(
+        accounts::SetPaused {
+            authority: *authority,
+            global_config: global_config_address(),
+        },
+        instruction::SetPaused { paused_operations },
+    )
+}
+
+// `challenge_id` must be the config's `next_challenge_id` when the transaction lands
+pub fn create_challenge(
+    setter: &Pubkey,
+    challenge_id: u64,
+    args: CreateChallengeArgs,
+    tokens: Option<TokenAccounts>,
+) -> Instruction {
+    let challenge = challenge_address(challenge_id);
+    let [mint, vault, setter_token_account, token_program] = token_fields(&challenge, tokens);
+    build(
+        accounts::CreateChallenge {
+            setter: *setter,
+            global_config: global_config_address(),
+            challenge_account: challenge,
+            system_program: system_program::ID,
+            mint,
+            vault,
+            setter_token_account,
+            token_program,
+        },
+        instruction::CreateChallenge {
+            question: args.question,
+            submission_deadline: args.submission_deadline,
+            answer_reveal_deadline: args.answer_reveal_deadline,
+            claim_deadline: args.claim_deadline,
+            entry_fee: args.entry_fee,
+            kind: args.kind,
+            solution_hash: args.solution_hash,
+            normalization: args.normalization,
+            setter_cut_bps: args.setter_cut_bps,
+            payout_strategy: args.payout_strategy,
+        },
+    )
+}
+
+pub fn create_submission(
+    submitter: &Pubkey,
+    challenge_id: u64,
+    encrypted_answer: [u8; 32],
+    tokens: Option<TokenAccounts>,
+) -> Instruction {
+    let challenge = challenge_address(challenge_id);
+    let [mint, vault, submitter_token_account, token_program] = token_fields(&challenge, tokens);
+    build(
+        accounts::CreateSubmission {
+            submitter: *submitter,
+            global_config: global_config_address(),
+            challenge_account: challenge,
+            submission_account: submission_address(&challenge, submitter),
+            system_program: system_program::ID,
+            mint,
+            vault,
+            submitter_token_account,
+            token_program,
+        },
+        instruction::CreateSubmission {
+            encrypted_answer,
+            commitment_version: riddle_rush::COMMITMENT_VERSION,
+        },
+    )
+}
+
+pub fn challenge_solution_reveal(user: &Pubkey, challenge_id: u64, plaintext_solution: String, salt: [u8; 32]) -> Instruction {
+    build(
+        accounts::ChallengeSolutionReveal {
+            user: *user,
+            global_config: global_config_address(),
+            challenge_account: challenge_address(challenge_id),
+            system_program: system_program::ID,
+        },
+        instruction::ChallengeSolutionReveal {
+            id: challenge_id,
+            plaintext_solution,
+            salt,
+        },
+    )
+}
+
+pub fn submission_solution_reveal(
+    submitter: &Pubkey,
+    challenge_id: u64,
+    salt: [u8; 32],
+    plaintext_answer: String,
+) -> Instruction {
+    let challenge = challenge_address(challenge_id);
+    build(
+        accounts::SubmissionSolutionReveal {
+            submitter: *submitter,
+            global_config: global_config_address(),
+            challenge_account: challenge,
+            submission_account: submission_address(&challenge, submitter),
+            system_program: system_program::ID,
+        },
+        instruction::SubmissionSolutionReveal { salt, plaintext_answer },
+    )
+}
+
+pub fn setter_claim(setter: &Pubkey, challenge_id: u64, tokens: Option<TokenAccounts>) -> Instruction {
+    let challenge = challenge_address(challenge_id);
+    let [mint, vault, setter_token_account, token_program] = token_fields(&challenge, tokens);
+    build(
+        accounts::SetterClaim {
+            setter: *setter,
+            global_config: global_config_address(),
+            challenge_account: challenge,
+            system_program: system_program::ID,
+            mint,
+            vault,
+            setter_token_account,
+            token_program,
+        },
+        instruction::SetterClaim {},
+    )
+}
+
+pub fn submitter_claim(submitter: &Pubkey, challenge_id: u64, tokens: Option<TokenAccounts>) -> Instruction {
+    let challenge = challenge_address(challenge_id);
+    let [mint, vault, submitter_token_account, token_program] = token_fields(&challenge, tokens);
+    build(
+        accounts::SubmitterClaim {
+            submitter: *submitter,
+            global_config: global_config_address(),
+            challenge_account: challenge,
+            submission_account: submission_address(&challenge, submitter),
+            system_program: system_program::ID,
+            mint,
+            vault,
+            submitter_token_account,
+            token_program,
+        },
+        instruction::SubmitterClaim {},
+    )
+}
+
+// Anyone may pay for the protocol fee to be sent to the configured treasury
+pub fn treasury_claim(payer: &Pubkey, treasury: &Pubkey, challenge_id: u64, tokens: Option<TokenAccounts>) -> Instruction {
+    let challenge = challenge_address(challenge_id);
+    let [mint, vault, treasury_token_account, token_program] = token_fields(&challenge, tokens);
+    build(
+        accounts::TreasuryClaim {
+            payer: *payer,
+            global_config: global_config_address(),
+            treasury: *treasury,
+            challenge_account: challenge,
+            system_program: system_program::ID,
+            mint,
+            vault,
+            treasury_token_account,
+            token_program,
+        },
+        instruction::TreasuryClaim {},
+    )
+}
+
+pub fn setter_close_challenge(setter: &Pubkey, challenge_id: u64, tokens: Option<TokenAccounts>) -> Instruction {
+    let challenge = challenge_address(challenge_id);
+    let [mint, vault, setter_token_account, token_program] = token_fields(&challenge, tokens);
+    build(
+        accounts::SetterClose {
+            setter: *setter,
+            global_config: global_config_address(),
+            challenge_account: challenge,
+            system_program: system_program::ID,
+            mint,
+            vault,
+            setter_token_account,
+            token_program,
+        },
+        instruction::SetterCloseChallenge {},
+    )
+}
+
+// Closes the submitter's own submission once it can't claim anything anymore
+pub fn close_submission(submitter: &Pubkey, challenge_id: u64) -> Instruction {
+    let challenge = challenge_address(challenge_id);
+    build(
+        accounts::CloseSubmission {
+            submitter: *submitter,
+            global_config: global_config_address(),
+            challenge_account: challenge,
+            submission_account: submission_address(&challenge, submitter),
+        },
+        instruction::CloseSubmission {},
+    )
+}
+
+// Anyone may close a submission after the claim deadline, the rent goes back to `submitter`
+pub fn sweep_submission(payer: &Pubkey, challenge_id: u64, submitter: &Pubkey) -> Instruction {
+    let challenge = challenge_address(challenge_id);
+    build(
+        accounts::SweepSubmission {
+            payer: *payer,
+            global_config: global_config_address(),
+            challenge_account: challenge,
+            submitter: *submitter,
+            submission_account: submission_address(&challenge, submitter),
+        },
+        instruction::SweepSubmission {},
+    )
+}
+
+// Anyone may refund a submitter once the answer reveal deadline passed without a solution. `tokens`
+// holds the submitter's token account, not the payer's
+pub fn refund(payer: &Pubkey, challenge_id: u64, submitter: &Pubkey, tokens: Option<TokenAccounts>) -> Instruction {
+    let challenge = challenge_address(challenge_id);
+    let [mint, vault, submitter_token_account, token_program] = token_fields(&challenge, tokens);
+    build(
+        accounts::Refund {
+            payer: *payer,
+            global_config: global_config_address(),
+            challenge_account: challenge,
+            submitter: *submitter,
+            submission_account: submission_address(&challenge, submitter),
+            system_program: system_program::ID,
+            mint,
+            vault,
+            submitter_token_account,
+            token_program,
+        },
+        instruction::Refund {},
+    )
+}
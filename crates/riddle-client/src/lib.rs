@@ -0,0 +1,35 @@
+//! Typed client for the Riddle Rush program: PDA derivation, instruction builders for every
+//! entrypoint, account and event decoders, the answer commitment and an encrypted nonce vault.
+pub mod decode;
+pub mod instructions;
+pub mod pda;
+pub mod vault;
+
+pub use decode::*;
+pub use instructions::*;
+pub use pda::*;
+pub use vault::*;
+
+use anchor_lang::prelude::Pubkey;
+pub use riddle_rush::{
+    AnswerNormalization, ChallengeAccount, ChallengeKind, GlobalConfig, PayoutStrategy, SubmissionAccount, COMMITMENT_VERSION,
+    ID as PROGRAM_ID,
+};
+
+// Commitment stored by `create_submission`, computed exactly as `submission_solution_reveal`
+// checks it for `COMMITMENT_VERSION`
+pub fn answer_commitment(challenge: &Pubkey, submitter: &Pubkey, answer: &str, nonce: &[u8; 32]) -> [u8; 32] {
+    riddle_rush::submission_commitment(challenge, submitter, answer, nonce)
+}
+
+// Solution hash committed by the setter of a text riddle, computed exactly as
+// `challenge_solution_reveal` checks it
+pub fn solution_commitment(challenge: &Pubkey, solution: &str, salt: &[u8; 32]) -> [u8; 32] {
+    riddle_rush::solution_commitment(challenge, solution, salt)
+}
+
+// Seed hash committed by the setter of a lottery, computed exactly as `challenge_solution_reveal`
+// checks it
+pub fn lottery_seed_commitment(challenge: &Pubkey, seed: &[u8; 32]) -> [u8; 32] {
+    riddle_rush::lottery_seed_commitment(challenge, seed)
+}
@@ -0,0 +1,25 @@
+// Addresses derived from the seeds used in the program's account constraints
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+pub fn global_config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"global_config"], &riddle_rush::ID).0
+}
+
+pub fn challenge_address(challenge_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"challenge", challenge_id.to_le_bytes().as_ref()], &riddle_rush::ID).0
+}
+
+pub fn submission_address(challenge: &Pubkey, submitter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"submission", challenge.as_ref(), submitter.as_ref()], &riddle_rush::ID).0
+}
+
+// Token account holding the entry fees of a challenge denominated in an SPL token
+pub fn vault_address(challenge: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", challenge.as_ref()], &riddle_rush::ID).0
+}
+
+// Program data account whose upgrade authority may initialize the config
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[riddle_rush::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
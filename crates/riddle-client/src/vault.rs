@@ -0,0 +1,237 @@
+// Encrypted local record of the answers and nonces committed on-chain. Losing a nonce makes the
+// answer impossible to reveal, so entries are written before the commitment is sent, and nonces can
+// also be derived again from the wallet with `nonce_message` and `derive_nonce`
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hashv;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hmac::Hmac;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+const VAULT_VERSION: u8 = 1;
+const KDF_ITERATIONS: u32 = 210_000;
+const NONCE_DOMAIN: &[u8] = b"riddle-rush:nonce:v1";
+
+#[derive(Debug)]
+pub enum VaultError {
+    Io(io::Error),
+    Format(String),
+    // Wrong password, or the file was modified
+    Decryption,
+}
+
+impl std::fmt::Display for VaultError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultError::Io(e) => write!(f, "{}", e),
+            VaultError::Format(e) => write!(f, "invalid vault: {}", e),
+            VaultError::Decryption => write!(f, "wrong vault password or corrupted vault"),
+        }
+    }
+}
+
+impl std::error::Error for VaultError {}
+
+impl From<io::Error> for VaultError {
+    fn from(e: io::Error) -> Self {
+        VaultError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for VaultError {
+    fn from(e: serde_json::Error) -> Self {
+        VaultError::Format(e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    // A player's answer and the nonce of its commitment
+    Submission,
+    // A text riddle setter's solution and the salt of its hash
+    Solution,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultEntry {
+    pub kind: SecretKind,
+    pub challenge_id: u64,
+    #[serde(with = "pubkey_string")]
+    pub challenge: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub owner: Pubkey,
+    pub answer: String,
+    #[serde(with = "hex_bytes")]
+    pub nonce: [u8; 32],
+}
+
+// On-disk layout, only the entries are encrypted
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u8,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+pub struct NonceVault {
+    path: PathBuf,
+    password: String,
+    entries: Vec<VaultEntry>,
+}
+
+impl NonceVault {
+    // Opens the vault at `path`, starting an empty one if the file doesn't exist yet
+    pub fn open(path: &Path, password: &str) -> Result<Self, VaultError> {
+        let entries = match fs::read_to_string(path) {
+            Ok(contents) => decrypt(&serde_json::from_str(&contents)?, password)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(NonceVault {
+            path: path.to_path_buf(),
+            password: password.to_string(),
+            entries,
+        })
+    }
+
+    pub fn entries(&self) -> &[VaultEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, kind: SecretKind, challenge: &Pubkey, owner: &Pubkey) -> Option<&VaultEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.kind == kind && e.challenge == *challenge && e.owner == *owner)
+    }
+
+    // Replaces any entry for the same challenge and owner, and saves the vault
+    pub fn insert(&mut self, entry: VaultEntry) -> Result<(), VaultError> {
+        self.entries
+            .retain(|e| !(e.kind == entry.kind && e.challenge == entry.challenge && e.owner == entry.owner));
+        self.entries.push(entry);
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), VaultError> {
+        let file = encrypt(&self.entries, &self.password)?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Written next to the vault and renamed over it, so a crash never leaves a truncated file
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(&file)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))?;
+        }
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+fn derive_key(password: &str, salt: &[u8], iterations: u32) -> Key {
+    let mut key = Key::default();
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+fn encrypt(entries: &[VaultEntry], password: &str) -> Result<VaultFile, VaultError> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let cipher = ChaCha20Poly1305::new(&derive_key(password, &salt, KDF_ITERATIONS));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), serde_json::to_vec(entries)?.as_ref())
+        .map_err(|_| VaultError::Format("encryption failed".to_string()))?;
+    Ok(VaultFile {
+        version: VAULT_VERSION,
+        iterations: KDF_ITERATIONS,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn decrypt(file: &VaultFile, password: &str) -> Result<Vec<VaultEntry>, VaultError> {
+    if file.version != VAULT_VERSION {
+        return Err(VaultError::Format(format!("unsupported version {}", file.version)));
+    }
+    // An edited count could make the key trivial to brute force, or take forever to derive
+    if file.iterations != KDF_ITERATIONS {
+        return Err(VaultError::Format(format!("unsupported key derivation iterations {}", file.iterations)));
+    }
+    let decode = |field: &str| BASE64.decode(field).map_err(|e| VaultError::Format(e.to_string()));
+    let nonce = decode(&file.nonce)?;
+    if nonce.len() != 12 {
+        return Err(VaultError::Format("bad nonce length".to_string()));
+    }
+    let cipher = ChaCha20Poly1305::new(&derive_key(password, &decode(&file.salt)?, file.iterations));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), decode(&file.ciphertext)?.as_ref())
+        .map_err(|_| VaultError::Decryption)?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+// Message the submitter signs to derive the nonce of a challenge. Ed25519 signatures are
+// deterministic, so signing it again with the same wallet recovers the same nonce
+pub fn nonce_message(challenge: &Pubkey) -> Vec<u8> {
+    [NONCE_DOMAIN, challenge.as_ref()].concat()
+}
+
+// Salt for `answer_commitment`, hashed so the signature itself is never revealed on-chain
+pub fn derive_nonce(signature: &[u8]) -> [u8; 32] {
+    hashv(&[NONCE_DOMAIN, signature]).to_bytes()
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+mod hex_bytes {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let mut bytes = [0u8; 32];
+        if s.len() != 64 || !s.is_ascii() {
+            return Err(serde::de::Error::custom("expected 32 hex encoded bytes"));
+        }
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).map_err(serde::de::Error::custom)?;
+        }
+        Ok(bytes)
+    }
+}
+
+mod pubkey_string {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(key)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Pubkey::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
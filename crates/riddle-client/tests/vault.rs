@@ -0,0 +1,96 @@
+// Round trips of the encrypted vault, and the ways opening an edited or foreign vault fails
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use riddle_client::{derive_nonce, nonce_message, NonceVault, SecretKind, VaultEntry, VaultError};
+use serde_json::Value;
+
+const PASSWORD: &str = "correct horse battery staple";
+
+fn vault_path(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("riddle-vault-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    directory.join("vault.json")
+}
+
+fn entry(kind: SecretKind, owner: Pubkey, answer: &str) -> VaultEntry {
+    VaultEntry {
+        kind,
+        challenge_id: 7,
+        challenge: Pubkey::new_unique(),
+        owner,
+        answer: answer.to_string(),
+        nonce: derive_nonce(answer.as_bytes()),
+    }
+}
+
+// Edits a field of the vault file on disk
+fn edit(path: &PathBuf, field: &str, value: Value) {
+    let mut file: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    file[field] = value;
+    std::fs::write(path, file.to_string()).unwrap();
+}
+
+#[test]
+fn entries_survive_a_round_trip() {
+    let path = vault_path("round-trip");
+    let owner = Pubkey::new_unique();
+    let submission = entry(SecretKind::Submission, owner, "42");
+    let solution = entry(SecretKind::Solution, owner, "A map");
+    {
+        let mut vault = NonceVault::open(&path, PASSWORD).unwrap();
+        assert!(vault.entries().is_empty());
+        vault.insert(submission.clone()).unwrap();
+        vault.insert(solution.clone()).unwrap();
+    }
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains("A map"));
+
+    let mut vault = NonceVault::open(&path, PASSWORD).unwrap();
+    assert_eq!(vault.entries(), [submission.clone(), solution.clone()]);
+    assert_eq!(vault.get(SecretKind::Solution, &solution.challenge, &owner), Some(&solution));
+    assert_eq!(vault.get(SecretKind::Submission, &solution.challenge, &owner), None);
+
+    // A new answer to the same challenge replaces the old one
+    let replaced = VaultEntry { answer: "43".to_string(), ..submission.clone() };
+    vault.insert(replaced.clone()).unwrap();
+    let vault = NonceVault::open(&path, PASSWORD).unwrap();
+    assert_eq!(vault.entries(), [solution, replaced]);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn wrong_password_and_tampering_are_detected() {
+    let path = vault_path("tampered");
+    NonceVault::open(&path, PASSWORD).unwrap().insert(entry(SecretKind::Submission, Pubkey::new_unique(), "42")).unwrap();
+    assert!(matches!(NonceVault::open(&path, "wrong password"), Err(VaultError::Decryption)));
+
+    // Flipping a bit of the ciphertext fails authentication
+    let original = std::fs::read_to_string(&path).unwrap();
+    let file: Value = serde_json::from_str(&original).unwrap();
+    let mut ciphertext = file["ciphertext"].as_str().unwrap().to_string().into_bytes();
+    ciphertext[0] = if ciphertext[0] == b'A' { b'B' } else { b'A' };
+    edit(&path, "ciphertext", Value::String(String::from_utf8(ciphertext).unwrap()));
+    assert!(matches!(NonceVault::open(&path, PASSWORD), Err(VaultError::Decryption)));
+
+    // Weakening or inflating the key derivation is rejected before deriving anything
+    for iterations in [0, 1, u32::MAX] {
+        std::fs::write(&path, &original).unwrap();
+        edit(&path, "iterations", Value::from(iterations));
+        assert!(matches!(NonceVault::open(&path, PASSWORD), Err(VaultError::Format(_))), "{}", iterations);
+    }
+    std::fs::write(&path, &original).unwrap();
+    edit(&path, "version", Value::from(2));
+    assert!(matches!(NonceVault::open(&path, PASSWORD), Err(VaultError::Format(_))));
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn nonces_are_deterministic() {
+    let [first, second] = [Pubkey::new_unique(), Pubkey::new_unique()];
+    assert_eq!(derive_nonce(b"signature"), derive_nonce(b"signature"));
+    assert_ne!(derive_nonce(b"signature"), derive_nonce(b"signaturf"));
+    assert_eq!(nonce_message(&first), nonce_message(&first));
+    assert_ne!(nonce_message(&first), nonce_message(&second));
+    assert!(nonce_message(&first).ends_with(first.as_ref()));
+}
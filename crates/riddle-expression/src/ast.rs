@@ -0,0 +1,82 @@
+use alloc::boxed::Box;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::{ExpressionError, Function, Rational, SourceError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(Rational),
+    Neg(Span, Box<Expr>),
+    Binary(BinaryOp, Span, Box<Expr>, Box<Expr>),
+    Call(Function, Span, Vec<Expr>),
+}
+
+// The derived drop glue would recurse once per level of the tree, so children are detached
+// and dropped one at a time instead
+impl Drop for Expr {
+    fn drop(&mut self) {
+        let mut detached = Vec::new();
+        self.detach_children(&mut detached);
+        while let Some(mut child) = detached.pop() {
+            child.detach_children(&mut detached);
+        }
+    }
+}
+
+impl Expr {
+    fn detach_children(&mut self, out: &mut Vec<Expr>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Neg(_, operand) => out.push(mem::replace(operand.as_mut(), Expr::Number(Rational::ZERO))),
+            Expr::Binary(_, _, lhs, rhs) => {
+                out.push(mem::replace(lhs.as_mut(), Expr::Number(Rational::ZERO)));
+                out.push(mem::replace(rhs.as_mut(), Expr::Number(Rational::ZERO)));
+            }
+            Expr::Call(_, _, args) => out.append(args),
+        }
+    }
+}
+
+// Location in the source of the operator or function name a node was parsed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn error(self, error: ExpressionError, source: &str) -> SourceError {
+        SourceError {
+            error,
+            offset: self.offset,
+            token: source.get(self.offset..self.offset + self.len).unwrap_or_default().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    FloorDiv,
+    Rem,
+    Pow,
+}
+
+impl BinaryOp {
+    pub fn apply(self, lhs: Rational, rhs: Rational) -> Result<Rational, ExpressionError> {
+        match self {
+            BinaryOp::Add => lhs.checked_add(rhs),
+            BinaryOp::Sub => lhs.checked_sub(rhs),
+            BinaryOp::Mul => lhs.checked_mul(rhs),
+            BinaryOp::Div => lhs.checked_div(rhs),
+            BinaryOp::FloorDiv => lhs.checked_floor_div(rhs),
+            BinaryOp::Rem => lhs.checked_rem(rhs),
+            BinaryOp::Pow => lhs.checked_pow(rhs),
+        }
+    }
+}
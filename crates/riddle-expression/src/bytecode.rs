@@ -0,0 +1,252 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{BinaryOp, Expr, ExpressionError, Function, Rational, Span};
+
+// Compact postfix encoding of a parsed expression, stored on the challenge account.
+// Layout: a version byte followed by a sequence of instructions for a stack machine.
+pub const BYTECODE_VERSION: u8 = 1;
+
+const OP_PUSH_INT: u8 = 0x01; // zigzag varint value
+const OP_PUSH_RATIO: u8 = 0x02; // zigzag varint numerator, varint denominator
+const OP_NEG: u8 = 0x10;
+const OP_ADD: u8 = 0x11;
+const OP_SUB: u8 = 0x12;
+const OP_MUL: u8 = 0x13;
+const OP_DIV: u8 = 0x14;
+const OP_FLOOR_DIV: u8 = 0x15;
+const OP_REM: u8 = 0x16;
+const OP_POW: u8 = 0x17;
+const OP_CALL: u8 = 0x20; // function id, argument count
+
+// Failure while running a program, `position` is the offset of the failing instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionError {
+    pub error: ExpressionError,
+    pub position: usize,
+}
+
+// Maps instruction offsets back to the source, only kept off-chain and while compiling
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    entries: Vec<(usize, Span)>,
+}
+
+impl SourceMap {
+    pub fn locate(&self, position: usize) -> Option<Span> {
+        self.entries
+            .iter()
+            .find(|(instruction, _)| *instruction == position)
+            .map(|(_, span)| *span)
+    }
+}
+
+pub fn compile(expr: &Expr) -> Vec<u8> {
+    compile_with_source_map(expr).0
+}
+
+pub fn compile_with_source_map(expr: &Expr) -> (Vec<u8>, SourceMap) {
+    let mut bytecode = vec![BYTECODE_VERSION];
+    let mut source_map = SourceMap::default();
+    emit(expr, &mut bytecode, &mut source_map);
+    (bytecode, source_map)
+}
+
+// Postfix walk of the tree: operands are visited before their operator is emitted
+enum Step<'a> {
+    Visit(&'a Expr),
+    Emit(&'a Expr),
+}
+
+// Walks the tree with an explicit stack, a left-leaning chain such as `1+1+…+1` is as deep as it
+// has operators and would otherwise recurse once per operator
+fn emit(expr: &Expr, out: &mut Vec<u8>, source_map: &mut SourceMap) {
+    let mut steps = vec![Step::Visit(expr)];
+    while let Some(step) = steps.pop() {
+        match step {
+            Step::Visit(expr) => {
+                steps.push(Step::Emit(expr));
+                match expr {
+                    Expr::Number(_) => {}
+                    Expr::Neg(_, operand) => steps.push(Step::Visit(operand)),
+                    Expr::Binary(_, _, lhs, rhs) => {
+                        steps.push(Step::Visit(rhs));
+                        steps.push(Step::Visit(lhs));
+                    }
+                    Expr::Call(_, _, args) => steps.extend(args.iter().rev().map(Step::Visit)),
+                }
+            }
+            Step::Emit(expr) => emit_node(expr, out, source_map),
+        }
+    }
+}
+
+// Emits a single node, its operands are already on the stack
+fn emit_node(expr: &Expr, out: &mut Vec<u8>, source_map: &mut SourceMap) {
+    match expr {
+        Expr::Number(value) if value.is_integer() => {
+            out.push(OP_PUSH_INT);
+            write_varint(zigzag(value.numer()), out);
+        }
+        Expr::Number(value) => {
+            out.push(OP_PUSH_RATIO);
+            write_varint(zigzag(value.numer()), out);
+            write_varint(value.denom() as u128, out);
+        }
+        Expr::Neg(span, _) => {
+            source_map.entries.push((out.len(), *span));
+            out.push(OP_NEG);
+        }
+        Expr::Binary(op, span, _, _) => {
+            source_map.entries.push((out.len(), *span));
+            out.push(binary_opcode(*op));
+        }
+        Expr::Call(function, span, args) => {
+            source_map.entries.push((out.len(), *span));
+            out.push(OP_CALL);
+            out.push(*function as u8);
+            // The parser rejects calls with more arguments than fit in a byte, see Function::check_arity
+            out.push(args.len() as u8);
+        }
+    }
+}
+
+pub fn execute(bytecode: &[u8]) -> Result<Rational, ExecutionError> {
+    let mut reader = Reader { bytecode, position: 0 };
+    let invalid = |position| ExecutionError {
+        error: ExpressionError::InvalidBytecode,
+        position,
+    };
+    if reader.byte() != Some(BYTECODE_VERSION) {
+        return Err(invalid(0));
+    }
+
+    let mut stack: Vec<Rational> = Vec::new();
+    while !reader.is_empty() {
+        let position = reader.position;
+        step(&mut reader, &mut stack).map_err(|error| ExecutionError { error, position })?;
+    }
+
+    match stack.as_slice() {
+        [result] => Ok(*result),
+        _ => Err(invalid(bytecode.len())),
+    }
+}
+
+// Runs the instruction at the reader position
+fn step(reader: &mut Reader, stack: &mut Vec<Rational>) -> Result<(), ExpressionError> {
+    let opcode = reader.byte().ok_or(ExpressionError::InvalidBytecode)?;
+    let value = match opcode {
+        OP_PUSH_INT => Rational::from_integer(unzigzag(reader.varint()?)),
+        OP_PUSH_RATIO => {
+            let numerator = unzigzag(reader.varint()?);
+            let denominator = i128::try_from(reader.varint()?).map_err(|_| ExpressionError::InvalidBytecode)?;
+            if denominator <= 0 {
+                return Err(ExpressionError::InvalidBytecode);
+            }
+            Rational::new(numerator, denominator)?
+        }
+        OP_NEG => pop(stack)?.checked_neg()?,
+        OP_CALL => {
+            let function = reader
+                .byte()
+                .and_then(Function::from_u8)
+                .ok_or(ExpressionError::InvalidBytecode)?;
+            let count = reader.byte().ok_or(ExpressionError::InvalidBytecode)? as usize;
+            if count > stack.len() {
+                return Err(ExpressionError::InvalidBytecode);
+            }
+            let args = stack.split_off(stack.len() - count);
+            function.apply(&args)?
+        }
+        opcode => {
+            let op = binary_op(opcode).ok_or(ExpressionError::InvalidBytecode)?;
+            let rhs = pop(stack)?;
+            let lhs = pop(stack)?;
+            op.apply(lhs, rhs)?
+        }
+    };
+    stack.push(value);
+    Ok(())
+}
+
+fn binary_opcode(op: BinaryOp) -> u8 {
+    match op {
+        BinaryOp::Add => OP_ADD,
+        BinaryOp::Sub => OP_SUB,
+        BinaryOp::Mul => OP_MUL,
+        BinaryOp::Div => OP_DIV,
+        BinaryOp::FloorDiv => OP_FLOOR_DIV,
+        BinaryOp::Rem => OP_REM,
+        BinaryOp::Pow => OP_POW,
+    }
+}
+
+fn binary_op(opcode: u8) -> Option<BinaryOp> {
+    match opcode {
+        OP_ADD => Some(BinaryOp::Add),
+        OP_SUB => Some(BinaryOp::Sub),
+        OP_MUL => Some(BinaryOp::Mul),
+        OP_DIV => Some(BinaryOp::Div),
+        OP_FLOOR_DIV => Some(BinaryOp::FloorDiv),
+        OP_REM => Some(BinaryOp::Rem),
+        OP_POW => Some(BinaryOp::Pow),
+        _ => None,
+    }
+}
+
+fn pop(stack: &mut Vec<Rational>) -> Result<Rational, ExpressionError> {
+    stack.pop().ok_or(ExpressionError::InvalidBytecode)
+}
+
+struct Reader<'a> {
+    bytecode: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn is_empty(&self) -> bool {
+        self.position >= self.bytecode.len()
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        let byte = *self.bytecode.get(self.position)?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    // Unsigned LEB128
+    fn varint(&mut self) -> Result<u128, ExpressionError> {
+        let mut value: u128 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte().ok_or(ExpressionError::InvalidBytecode)?;
+            // Only the two lowest bits of the 19th byte still fit into a u128
+            if shift == 126 && byte > 0x03 {
+                return Err(ExpressionError::InvalidBytecode);
+            }
+            value |= ((byte & 0x7f) as u128) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+}
+
+fn write_varint(mut value: u128, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+// Maps signed values onto unsigned ones so that small magnitudes stay short
+fn zigzag(value: i128) -> u128 {
+    ((value << 1) ^ (value >> 127)) as u128
+}
+
+fn unzigzag(value: u128) -> i128 {
+    ((value >> 1) as i128) ^ -((value & 1) as i128)
+}
@@ -0,0 +1,208 @@
+use crate::{ExpressionError, Rational};
+
+// Named constants that can be used anywhere a number is expected
+const CONSTANTS: &[(&str, i128)] = &[
+    ("dozen", 12),
+    ("score", 20),
+    ("hundred", 100),
+    ("gross", 144),
+    ("thousand", 1_000),
+    ("million", 1_000_000),
+];
+
+pub fn lookup_constant(name: &str) -> Result<Rational, ExpressionError> {
+    CONSTANTS
+        .iter()
+        .find(|(constant, _)| *constant == name)
+        .map(|(_, value)| Rational::from_integer(*value))
+        .ok_or(ExpressionError::UnknownConstant)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Function {
+    Abs = 0,
+    Min = 1,
+    Max = 2,
+    Gcd = 3,
+    Lcm = 4,
+    Sqrt = 5,
+    Factorial = 6,
+    Binomial = 7,
+    Fib = 8,
+}
+
+impl Function {
+    const ALL: [Function; 9] = [
+        Function::Abs,
+        Function::Min,
+        Function::Max,
+        Function::Gcd,
+        Function::Lcm,
+        Function::Sqrt,
+        Function::Factorial,
+        Function::Binomial,
+        Function::Fib,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Function::Abs => "abs",
+            Function::Min => "min",
+            Function::Max => "max",
+            Function::Gcd => "gcd",
+            Function::Lcm => "lcm",
+            Function::Sqrt => "sqrt",
+            Function::Factorial => "factorial",
+            Function::Binomial => "binomial",
+            Function::Fib => "fib",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, ExpressionError> {
+        Function::ALL
+            .into_iter()
+            .find(|function| function.name() == name)
+            .ok_or(ExpressionError::UnknownFunction)
+    }
+
+    pub fn from_u8(id: u8) -> Option<Self> {
+        Function::ALL.get(id as usize).copied()
+    }
+
+    // Checked when the call is parsed so that a compiled program never fails on arity. The bytecode
+    // stores the count in a byte, whatever token limit the expression was parsed with
+    pub fn check_arity(self, count: usize) -> Result<(), ExpressionError> {
+        let valid = match self {
+            Function::Min | Function::Max => (2..=u8::MAX as usize).contains(&count),
+            Function::Gcd | Function::Lcm | Function::Binomial => count == 2,
+            Function::Abs | Function::Sqrt | Function::Factorial | Function::Fib => count == 1,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(ExpressionError::WrongArgumentCount)
+        }
+    }
+
+    pub fn apply(self, args: &[Rational]) -> Result<Rational, ExpressionError> {
+        self.check_arity(args.len())?;
+        match self {
+            Function::Abs => {
+                let [x] = expect_args::<1>(args)?;
+                if x.numer() < 0 {
+                    x.checked_neg()
+                } else {
+                    Ok(x)
+                }
+            }
+            Function::Min | Function::Max => {
+                let mut best = args[0];
+                for &arg in &args[1..] {
+                    // Compared without subtracting, so operands of opposite sign near the bounds can't overflow
+                    let better = if self == Function::Min { arg < best } else { arg > best };
+                    if better {
+                        best = arg;
+                    }
+                }
+                Ok(best)
+            }
+            Function::Gcd => {
+                let [a, b] = expect_args::<2>(args)?;
+                let g = gcd(integer(a)?, integer(b)?)?;
+                Ok(Rational::from_integer(g))
+            }
+            Function::Lcm => {
+                let [a, b] = expect_args::<2>(args)?;
+                let (a, b) = (integer(a)?, integer(b)?);
+                if a == 0 || b == 0 {
+                    return Ok(Rational::ZERO);
+                }
+                let lcm = (a / gcd(a, b)?)
+                    .checked_mul(b)
+                    .and_then(i128::checked_abs)
+                    .ok_or(ExpressionError::Overflow)?;
+                Ok(Rational::from_integer(lcm))
+            }
+            Function::Sqrt => {
+                let [n] = expect_args::<1>(args)?;
+                Ok(Rational::from_integer(isqrt(non_negative_integer(n)?)))
+            }
+            Function::Factorial => {
+                let [n] = expect_args::<1>(args)?;
+                let mut result: i128 = 1;
+                for i in 2..=non_negative_integer(n)? {
+                    result = result.checked_mul(i).ok_or(ExpressionError::Overflow)?;
+                }
+                Ok(Rational::from_integer(result))
+            }
+            Function::Binomial => {
+                let [n, k] = expect_args::<2>(args)?;
+                let (n, k) = (non_negative_integer(n)?, non_negative_integer(k)?);
+                if k > n {
+                    return Ok(Rational::ZERO);
+                }
+                // Multiplicative formula, every partial product is itself a binomial coefficient
+                let k = k.min(n - k);
+                let mut result: i128 = 1;
+                for i in 1..=k {
+                    let g = gcd(result, i)?;
+                    result = (result / g)
+                        .checked_mul((n - k + i) / (i / g))
+                        .ok_or(ExpressionError::Overflow)?;
+                }
+                Ok(Rational::from_integer(result))
+            }
+            Function::Fib => {
+                let [n] = expect_args::<1>(args)?;
+                let (mut current, mut next): (i128, i128) = (0, 1);
+                for _ in 0..non_negative_integer(n)? {
+                    let sum = current.checked_add(next).ok_or(ExpressionError::Overflow)?;
+                    (current, next) = (next, sum);
+                }
+                Ok(Rational::from_integer(current))
+            }
+        }
+    }
+}
+
+fn expect_args<const N: usize>(args: &[Rational]) -> Result<[Rational; N], ExpressionError> {
+    args.try_into().map_err(|_| ExpressionError::WrongArgumentCount)
+}
+
+fn integer(value: Rational) -> Result<i128, ExpressionError> {
+    if value.is_integer() {
+        Ok(value.numer())
+    } else {
+        Err(ExpressionError::InvalidArgument)
+    }
+}
+
+fn non_negative_integer(value: Rational) -> Result<i128, ExpressionError> {
+    match integer(value)? {
+        n if n >= 0 => Ok(n),
+        _ => Err(ExpressionError::InvalidArgument),
+    }
+}
+
+fn gcd(a: i128, b: i128) -> Result<i128, ExpressionError> {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    i128::try_from(a).map_err(|_| ExpressionError::Overflow)
+}
+
+// Integer square root rounded down, using Newton's method
+fn isqrt(n: i128) -> i128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = x / 2 + 1;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
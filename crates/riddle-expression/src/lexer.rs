@@ -0,0 +1,103 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::{ExpressionError, SourceError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind<'a> {
+    Number(&'a str),
+    Identifier(&'a str),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    SlashSlash,
+    Percent,
+    Caret,
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    // Byte offset of the first character of the token in the source
+    pub offset: usize,
+    pub text: &'a str,
+}
+
+impl Token<'_> {
+    pub fn error(&self, error: ExpressionError) -> SourceError {
+        SourceError {
+            error,
+            offset: self.offset,
+            token: self.text.to_string(),
+        }
+    }
+}
+
+pub fn tokenize(source: &str, max_tokens: usize) -> Result<Vec<Token<'_>>, SourceError> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+
+    while let Some(c) = source[offset..].chars().next() {
+        if c.is_whitespace() {
+            offset += c.len_utf8();
+            continue;
+        }
+
+        let start = offset;
+        let kind = match c {
+            '0'..='9' | '.' => {
+                while offset < bytes.len() && (bytes[offset].is_ascii_digit() || bytes[offset] == b'.') {
+                    offset += 1;
+                }
+                TokenKind::Number(&source[start..offset])
+            }
+            'a'..='z' | 'A'..='Z' => {
+                while offset < bytes.len() && (bytes[offset].is_ascii_alphanumeric() || bytes[offset] == b'_') {
+                    offset += 1;
+                }
+                TokenKind::Identifier(&source[start..offset])
+            }
+            '/' if bytes.get(offset + 1) == Some(&b'/') => {
+                offset += 2;
+                TokenKind::SlashSlash
+            }
+            _ => {
+                offset += c.len_utf8();
+                match c {
+                    '+' => TokenKind::Plus,
+                    '-' => TokenKind::Minus,
+                    '*' => TokenKind::Star,
+                    '/' => TokenKind::Slash,
+                    '%' => TokenKind::Percent,
+                    '^' => TokenKind::Caret,
+                    '(' => TokenKind::LeftParen,
+                    ')' => TokenKind::RightParen,
+                    ',' => TokenKind::Comma,
+                    _ => {
+                        return Err(SourceError {
+                            error: ExpressionError::InvalidCharacter(c),
+                            offset: start,
+                            token: c.to_string(),
+                        })
+                    }
+                }
+            }
+        };
+        let token = Token {
+            kind,
+            offset: start,
+            text: &source[start..offset],
+        };
+        if tokens.len() == max_tokens {
+            return Err(token.error(ExpressionError::TooManyTokens));
+        }
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
@@ -0,0 +1,141 @@
+//! Exact arithmetic expressions used as Riddle Rush questions.
+//!
+//! The on-chain program compiles every question with this crate and executes the
+//! stored bytecode when the solution is revealed, so clients linking the same
+//! version get exactly the answer the program will store.
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+pub mod ast;
+pub mod bytecode;
+pub mod functions;
+pub mod lexer;
+pub mod parser;
+pub mod rational;
+
+pub use ast::*;
+pub use bytecode::*;
+pub use functions::*;
+pub use parser::*;
+pub use rational::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpressionError {
+    InvalidCharacter(char),
+    InvalidNumber,
+    UnexpectedEnd,
+    DivisionByZero,
+    Overflow,
+    NonIntegerExponent,
+    UnknownFunction,
+    UnknownConstant,
+    WrongArgumentCount,
+    InvalidArgument,
+    InvalidBytecode,
+    NestingTooDeep,
+    TooManyTokens,
+    TooManyOperations,
+    UnexpectedToken,
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpressionError::InvalidCharacter(c) => write!(f, "invalid character {:?}", c),
+            ExpressionError::InvalidNumber => write!(f, "invalid number"),
+            ExpressionError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            ExpressionError::DivisionByZero => write!(f, "division by zero"),
+            ExpressionError::Overflow => write!(f, "arithmetic overflow"),
+            ExpressionError::NonIntegerExponent => write!(f, "exponent is not an integer"),
+            ExpressionError::UnknownFunction => write!(f, "unknown function"),
+            ExpressionError::UnknownConstant => write!(f, "unknown constant"),
+            ExpressionError::WrongArgumentCount => write!(f, "wrong number of arguments"),
+            ExpressionError::InvalidArgument => write!(f, "invalid function argument"),
+            ExpressionError::InvalidBytecode => write!(f, "invalid bytecode"),
+            ExpressionError::NestingTooDeep => write!(f, "expression nested too deeply"),
+            ExpressionError::TooManyTokens => write!(f, "too many tokens"),
+            ExpressionError::TooManyOperations => write!(f, "too many operations"),
+            ExpressionError::UnexpectedToken => write!(f, "unexpected token"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ExpressionError {}
+
+// An expression error located in the source, so editors can highlight it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceError {
+    pub error: ExpressionError,
+    // Byte offset of the offending token, the source length at the end of input
+    pub offset: usize,
+    pub token: String,
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.error, self.offset)?;
+        if !self.token.is_empty() {
+            write!(f, " ({:?})", self.token)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SourceError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledExpression {
+    pub bytecode: Vec<u8>,
+    pub source_map: SourceMap,
+}
+
+impl CompiledExpression {
+    pub fn locate(&self, source: &str, e: ExecutionError) -> SourceError {
+        match self.source_map.locate(e.position) {
+            Some(span) => span.error(e.error, source),
+            None => SourceError {
+                error: e.error,
+                offset: 0,
+                token: String::new(),
+            },
+        }
+    }
+}
+
+// Parses and compiles the expression into the bytecode stored on the challenge
+pub fn compile_expression(expr: &str, limits: &ExpressionLimits) -> Result<CompiledExpression, SourceError> {
+    let ast = parse(expr, limits)?;
+    let (bytecode, source_map) = compile_with_source_map(&ast);
+    Ok(CompiledExpression { bytecode, source_map })
+}
+
+pub fn execute_bytecode(bytecode: &[u8]) -> Result<Rational, ExecutionError> {
+    execute(bytecode)
+}
+
+// Recompiles the source to find where a failure of its bytecode comes from
+pub fn locate_execution_error(source: &str, e: ExecutionError) -> SourceError {
+    match compile_expression(source, &ExpressionLimits::default()) {
+        Ok(compiled) => compiled.locate(source, e),
+        Err(_) => SourceError {
+            error: e.error,
+            offset: 0,
+            token: String::new(),
+        },
+    }
+}
+
+// Computes the canonical answer exactly as `challenge_solution_reveal` stores it
+pub fn evaluate_expression(expr: &str) -> Result<Rational, SourceError> {
+    let compiled = compile_expression(expr, &ExpressionLimits::default())?;
+    execute_bytecode(&compiled.bytecode).map_err(|e| compiled.locate(expr, e))
+}
@@ -0,0 +1,274 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::lexer::{tokenize, Token, TokenKind};
+use crate::{lookup_constant, BinaryOp, Expr, ExpressionError, Function, Rational, SourceError, Span};
+
+// Limits enforced on-chain, clients must use the same ones to preview answers
+// Each nesting level recurses through up to 7 parser frames (`parse_unary`, `parse_signed`, `parse_power`,
+// `parse_factor`, `parse_arguments`, `parse_expression`, `parse_term`), so 6 levels take at most 42 of
+// the 64 SBF call frames, leaving the rest to the entrypoint and handler. Compiling and dropping the
+// syntax tree don't recurse, so long operator chains don't count towards it
+pub const DEFAULT_MAX_DEPTH: usize = 6;
+pub const DEFAULT_MAX_TOKENS: usize = 128;
+pub const DEFAULT_MAX_OPERATIONS: usize = 48;
+
+// Bounds on the size of an expression, checked while parsing so that the compiled
+// program can always be executed within the compute budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpressionLimits {
+    pub max_depth: usize,
+    pub max_tokens: usize,
+    pub max_operations: usize,
+}
+
+impl Default for ExpressionLimits {
+    fn default() -> Self {
+        ExpressionLimits {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_tokens: DEFAULT_MAX_TOKENS,
+            max_operations: DEFAULT_MAX_OPERATIONS,
+        }
+    }
+}
+
+// Recursive descent parser, from loosest to tightest binding:
+//   expression := term (('+' | '-') term)*
+//   term       := unary (('*' | '/' | '//' | '%') unary)*
+//   unary      := ('+' | '-') unary | power
+//   power      := factor ('^' unary)?
+//   factor     := number | constant | function '(' arguments ')' | '(' expression ')'
+pub fn parse(source: &str, limits: &ExpressionLimits) -> Result<Expr, SourceError> {
+    let tokens = tokenize(source, limits.max_tokens)?;
+    let mut parser = Parser {
+        source,
+        tokens,
+        position: 0,
+        limits,
+        depth: 0,
+        operations: 0,
+    };
+    let expr = parser.parse_expression()?;
+    // Anything left over (e.g. an unmatched closing parenthesis) is an error
+    match parser.peek() {
+        Some(token) => Err(parser.unexpected(Some(token))),
+        None => Ok(expr),
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    limits: &'a ExpressionLimits,
+    depth: usize,
+    operations: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn peek_kind(&self) -> Option<TokenKind<'a>> {
+        self.peek().map(|token| token.kind)
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    // Errors without a token happened at the end of the input
+    fn error_at(&self, token: Option<Token<'a>>, error: ExpressionError) -> SourceError {
+        match token {
+            Some(token) => token.error(error),
+            None => SourceError {
+                error,
+                offset: self.source.len(),
+                token: String::new(),
+            },
+        }
+    }
+
+    fn unexpected(&self, token: Option<Token<'a>>) -> SourceError {
+        match token {
+            Some(token) => token.error(ExpressionError::UnexpectedToken),
+            None => self.error_at(None, ExpressionError::UnexpectedEnd),
+        }
+    }
+
+    // Every operator and function call node counts towards the operation limit
+    fn count_operation(&mut self, token: Token<'a>) -> Result<Span, SourceError> {
+        self.operations += 1;
+        if self.operations > self.limits.max_operations {
+            return Err(token.error(ExpressionError::TooManyOperations));
+        }
+        Ok(Span {
+            offset: token.offset,
+            len: token.text.len(),
+        })
+    }
+
+    // Consumes an operator token the caller has already peeked at
+    fn next_operation(&mut self) -> Result<Span, SourceError> {
+        let token = self.next().expect("operator token was peeked");
+        self.count_operation(token)
+    }
+
+    fn expect_right_paren(&mut self) -> Result<(), SourceError> {
+        match self.next() {
+            Some(Token { kind: TokenKind::RightParen, .. }) => Ok(()),
+            token => Err(self.unexpected(token)),
+        }
+    }
+
+    fn parse_expression(&mut self) -> Result<Expr, SourceError> {
+        let mut result = self.parse_term()?;
+
+        loop {
+            let op = match self.peek_kind() {
+                Some(TokenKind::Plus) => BinaryOp::Add,
+                Some(TokenKind::Minus) => BinaryOp::Sub,
+                _ => break,
+            };
+            let span = self.next_operation()?;
+            result = Expr::Binary(op, span, Box::new(result), Box::new(self.parse_term()?));
+        }
+
+        Ok(result)
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, SourceError> {
+        let mut result = self.parse_unary()?;
+
+        loop {
+            let op = match self.peek_kind() {
+                Some(TokenKind::Star) => BinaryOp::Mul,
+                Some(TokenKind::Slash) => BinaryOp::Div,
+                Some(TokenKind::SlashSlash) => BinaryOp::FloorDiv,
+                Some(TokenKind::Percent) => BinaryOp::Rem,
+                _ => break,
+            };
+            let span = self.next_operation()?;
+            result = Expr::Binary(op, span, Box::new(result), Box::new(self.parse_unary()?));
+        }
+
+        Ok(result)
+    }
+
+    // Every nested parenthesis, sign, exponent and call argument goes through here,
+    // so this is where the recursion depth is bounded
+    fn parse_unary(&mut self) -> Result<Expr, SourceError> {
+        self.depth += 1;
+        if self.depth > self.limits.max_depth {
+            return Err(self.error_at(self.peek(), ExpressionError::NestingTooDeep));
+        }
+        let result = self.parse_signed();
+        self.depth -= 1;
+        result
+    }
+
+    // Unary signs bind looser than `^`, so `-2^2` is `-(2^2)`
+    fn parse_signed(&mut self) -> Result<Expr, SourceError> {
+        match self.peek_kind() {
+            Some(TokenKind::Minus) => {
+                let span = self.next_operation()?;
+                Ok(Expr::Neg(span, Box::new(self.parse_unary()?)))
+            }
+            Some(TokenKind::Plus) => {
+                self.next();
+                self.parse_unary()
+            }
+            _ => self.parse_power(),
+        }
+    }
+
+    // Exponentiation is right-associative: `2^3^2` is `2^(3^2)`
+    fn parse_power(&mut self) -> Result<Expr, SourceError> {
+        let base = self.parse_factor()?;
+
+        if self.peek_kind() == Some(TokenKind::Caret) {
+            let span = self.next_operation()?;
+            let exponent = self.parse_unary()?;
+            return Ok(Expr::Binary(BinaryOp::Pow, span, Box::new(base), Box::new(exponent)));
+        }
+
+        Ok(base)
+    }
+
+    fn parse_factor(&mut self) -> Result<Expr, SourceError> {
+        let token = match self.next() {
+            Some(token) => token,
+            None => return Err(self.unexpected(None)),
+        };
+        match token.kind {
+            TokenKind::Number(literal) => match parse_number(literal) {
+                Ok(value) => Ok(Expr::Number(value)),
+                Err(e) => Err(token.error(e)),
+            },
+            TokenKind::LeftParen => {
+                let result = self.parse_expression()?;
+                self.expect_right_paren()?;
+                Ok(result)
+            }
+            TokenKind::Identifier(name) => {
+                if self.peek_kind() != Some(TokenKind::LeftParen) {
+                    return lookup_constant(name).map(Expr::Number).map_err(|e| token.error(e));
+                }
+                self.next();
+                let span = self.count_operation(token)?;
+                let function = Function::from_name(name).map_err(|e| token.error(e))?;
+                let args = self.parse_arguments()?;
+                function.check_arity(args.len()).map_err(|e| token.error(e))?;
+                Ok(Expr::Call(function, span, args))
+            }
+            _ => Err(self.unexpected(Some(token))),
+        }
+    }
+
+    // Comma separated call arguments, the opening parenthesis is already consumed
+    fn parse_arguments(&mut self) -> Result<Vec<Expr>, SourceError> {
+        let mut args = Vec::new();
+        if self.peek_kind() == Some(TokenKind::RightParen) {
+            self.next();
+            return Ok(args);
+        }
+
+        loop {
+            args.push(self.parse_expression()?);
+            let token = self.next();
+            match token.map(|token| token.kind) {
+                Some(TokenKind::Comma) => continue,
+                Some(TokenKind::RightParen) => return Ok(args),
+                _ => return Err(self.unexpected(token)),
+            }
+        }
+    }
+}
+
+// Parses a decimal literal such as `42`, `4.5` or `.25` into an exact fraction
+pub(crate) fn parse_number(num_str: &str) -> Result<Rational, ExpressionError> {
+    let (int_part, frac_part) = match num_str.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, frac_part),
+        None => (num_str, ""),
+    };
+    if (int_part.is_empty() && frac_part.is_empty()) || frac_part.contains('.') {
+        return Err(ExpressionError::InvalidNumber);
+    }
+
+    let mut numerator: i128 = 0;
+    for digit in int_part.chars().chain(frac_part.chars()) {
+        numerator = numerator
+            .checked_mul(10)
+            .and_then(|n| n.checked_add(digit.to_digit(10).unwrap() as i128))
+            .ok_or(ExpressionError::Overflow)?;
+    }
+    let denominator = 10i128
+        .checked_pow(frac_part.len() as u32)
+        .ok_or(ExpressionError::Overflow)?;
+
+    Rational::new(numerator, denominator)
+}
@@ -0,0 +1,251 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::parser::parse_number;
+use crate::ExpressionError;
+
+// Exact fraction kept in lowest terms with a strictly positive denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    pub fn new(num: i128, den: i128) -> Result<Self, ExpressionError> {
+        if den == 0 {
+            return Err(ExpressionError::DivisionByZero);
+        }
+        let g = gcd(num.unsigned_abs(), den.unsigned_abs());
+        // g only exceeds i128::MAX when den is i128::MIN
+        let g = i128::try_from(g).map_err(|_| ExpressionError::Overflow)?;
+        let (mut num, mut den) = (num / g, den / g);
+        if den < 0 {
+            num = num.checked_neg().ok_or(ExpressionError::Overflow)?;
+            den = den.checked_neg().ok_or(ExpressionError::Overflow)?;
+        }
+        Ok(Rational { num, den })
+    }
+
+    pub fn from_integer(value: i128) -> Self {
+        Rational { num: value, den: 1 }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn checked_neg(self) -> Result<Self, ExpressionError> {
+        let num = self.num.checked_neg().ok_or(ExpressionError::Overflow)?;
+        Ok(Rational { num, den: self.den })
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, ExpressionError> {
+        // a/b + c/d = (a * (l/b) + c * (l/d)) / l, with l = lcm(b, d)
+        let g = gcd_i128(self.den, other.den);
+        let lhs_scale = other.den / g;
+        let rhs_scale = self.den / g;
+        let den = self.den.checked_mul(lhs_scale).ok_or(ExpressionError::Overflow)?;
+        let lhs = self.num.checked_mul(lhs_scale).ok_or(ExpressionError::Overflow)?;
+        let rhs = other.num.checked_mul(rhs_scale).ok_or(ExpressionError::Overflow)?;
+        let num = lhs.checked_add(rhs).ok_or(ExpressionError::Overflow)?;
+        Rational::new(num, den)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, ExpressionError> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Result<Self, ExpressionError> {
+        // Cross-reduce first so intermediate products stay as small as possible
+        let g1 = gcd_i128(self.num, other.den);
+        let g2 = gcd_i128(other.num, self.den);
+        let num = (self.num / g1)
+            .checked_mul(other.num / g2)
+            .ok_or(ExpressionError::Overflow)?;
+        let den = (self.den / g2)
+            .checked_mul(other.den / g1)
+            .ok_or(ExpressionError::Overflow)?;
+        Rational::new(num, den)
+    }
+
+    pub fn checked_div(self, other: Self) -> Result<Self, ExpressionError> {
+        if other.num == 0 {
+            return Err(ExpressionError::DivisionByZero);
+        }
+        self.checked_mul(Rational::new(other.den, other.num)?)
+    }
+
+    // Largest integer not greater than the fraction
+    pub fn floor(self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    // Floored division, the result is always an integer: 7 // 2 = 3, -7 // 2 = -4
+    pub fn checked_floor_div(self, other: Self) -> Result<Self, ExpressionError> {
+        Ok(Rational::from_integer(self.checked_div(other)?.floor()))
+    }
+
+    // Remainder matching floored division: a = b * (a // b) + a % b
+    pub fn checked_rem(self, other: Self) -> Result<Self, ExpressionError> {
+        let quotient = self.checked_floor_div(other)?;
+        self.checked_sub(other.checked_mul(quotient)?)
+    }
+
+    // Raises the fraction to an integer power using exponentiation by squaring
+    pub fn checked_pow(self, exponent: Self) -> Result<Self, ExpressionError> {
+        if !exponent.is_integer() {
+            return Err(ExpressionError::NonIntegerExponent);
+        }
+        let base = if exponent.num < 0 {
+            Rational::ONE.checked_div(self)?
+        } else {
+            self
+        };
+
+        let mut remaining = exponent.num.unsigned_abs();
+        let mut square = base;
+        let mut result = Rational::ONE;
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = result.checked_mul(square)?;
+            }
+            remaining >>= 1;
+            if remaining > 0 {
+                square = square.checked_mul(square)?;
+            }
+        }
+        Ok(result)
+    }
+
+    // Renders the number as an exact decimal when the denominator only has the
+    // prime factors 2 and 5, otherwise returns None
+    fn to_decimal_string(self) -> Option<String> {
+        let mut rest = self.den;
+        let (mut twos, mut fives) = (0u32, 0u32);
+        while rest % 2 == 0 {
+            rest /= 2;
+            twos += 1;
+        }
+        while rest % 5 == 0 {
+            rest /= 5;
+            fives += 1;
+        }
+        if rest != 1 {
+            return None;
+        }
+
+        let digits = twos.max(fives);
+        let scale = 10i128.checked_pow(digits)?;
+        let scaled = self.num.checked_mul(scale / self.den)?;
+        let magnitude = scaled.unsigned_abs().to_string();
+        let magnitude = format!("{:0>width$}", magnitude, width = digits as usize + 1);
+        let (int_part, frac_part) = magnitude.split_at(magnitude.len() - digits as usize);
+        let sign = if scaled < 0 { "-" } else { "" };
+        Some(format!("{}{}.{}", sign, int_part, frac_part))
+    }
+}
+
+impl Ord for Rational {
+    // Compares without any arithmetic that could overflow: the floors first, then the fractional
+    // parts r/b and s/d, both in [0, 1), by cross-multiplying them in 256 bits
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.floor().cmp(&other.floor()).then_with(|| {
+            let lhs = self.num.rem_euclid(self.den) as u128;
+            let rhs = other.num.rem_euclid(other.den) as u128;
+            widening_mul(lhs, other.den as u128).cmp(&widening_mul(rhs, self.den as u128))
+        })
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    // Canonical form stored as the challenge solution: `42`, `-1.5` or `10/3`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            return write!(f, "{}", self.num);
+        }
+        match self.to_decimal_string() {
+            Some(decimal) => write!(f, "{}", decimal),
+            None => write!(f, "{}/{}", self.num, self.den),
+        }
+    }
+}
+
+impl FromStr for Rational {
+    type Err = ExpressionError;
+
+    // Parses a numeric literal such as `18`, `+18.0`, `-.5` or `36/2`, so answers
+    // can be compared by value
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let mut value = match unsigned.split_once('/') {
+            Some((numer, denom)) => parse_decimal(numer)?.checked_div(parse_decimal(denom)?)?,
+            None => parse_decimal(unsigned)?,
+        };
+        if negative {
+            value = value.checked_neg()?;
+        }
+        Ok(value)
+    }
+}
+
+fn parse_decimal(s: &str) -> Result<Rational, ExpressionError> {
+    if !s.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Err(ExpressionError::InvalidNumber);
+    }
+    parse_number(s)
+}
+
+// Full 256-bit product as (high, low) halves, which compare in the same order as the product
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+    let low = a_lo * b_lo;
+    let cross_a = a_hi * b_lo;
+    let cross_b = a_lo * b_hi;
+    let middle = (low >> 64) + (cross_a & MASK) + (cross_b & MASK);
+    let high = a_hi * b_hi + (cross_a >> 64) + (cross_b >> 64) + (middle >> 64);
+    (high, (middle << 64) | (low & MASK))
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    if a == 0 {
+        1
+    } else {
+        a
+    }
+}
+
+// Always called with at least one (positive) denominator, so the result is
+// bounded by it and fits back into an i128
+fn gcd_i128(a: i128, b: i128) -> i128 {
+    gcd(a.unsigned_abs(), b.unsigned_abs()) as i128
+}
@@ -0,0 +1,417 @@
+// Property-based and differential tests for the expression evaluator. Random
+// expressions are rendered to text, evaluated by the crate and compared against a
+// reference evaluator over arbitrary precision rationals.
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use proptest::prelude::*;
+use riddle_expression::{
+    compile_expression, evaluate_expression, execute_bytecode, ExpressionError, ExpressionLimits, Function, Rational,
+    SourceError,
+};
+
+// Generated expressions are larger than the on-chain limits allow
+const RELAXED_LIMITS: ExpressionLimits = ExpressionLimits {
+    max_depth: 256,
+    max_tokens: 4096,
+    max_operations: 4096,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    FloorDiv,
+    Rem,
+    Pow,
+}
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Sub => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+            Op::FloorDiv => "//",
+            Op::Rem => "%",
+            Op::Pow => "^",
+        }
+    }
+
+    fn precedence(self) -> u8 {
+        match self {
+            Op::Add | Op::Sub => 1,
+            Op::Mul | Op::Div | Op::FloorDiv | Op::Rem => 2,
+            Op::Pow => 4,
+        }
+    }
+}
+
+// Independent description of an expression, used to render text and as the
+// input of the reference evaluator
+#[derive(Debug, Clone)]
+enum Model {
+    Literal(&'static str),
+    Neg(Box<Model>),
+    Binary(Op, Box<Model>, Box<Model>),
+    Call(&'static str, Vec<Model>),
+}
+
+const LITERALS: &[&str] = &["0", "1", "2", "3", "5", "7", "10", "12", "0.5", "2.5", ".25", "dozen", "score"];
+const PREC_NEG: u8 = 3;
+const PREC_ATOM: u8 = 5;
+
+impl Model {
+    fn precedence(&self) -> u8 {
+        match self {
+            Model::Literal(_) | Model::Call(..) => PREC_ATOM,
+            Model::Neg(_) => PREC_NEG,
+            Model::Binary(op, ..) => op.precedence(),
+        }
+    }
+
+    // Renders with the fewest parentheses the grammar allows, so any mistake in
+    // precedence or associativity changes the parsed tree
+    fn minimal(&self) -> String {
+        match self {
+            Model::Literal(literal) => literal.to_string(),
+            Model::Neg(operand) => format!("-{}", operand.minimal_at(PREC_NEG)),
+            Model::Binary(Op::Pow, base, exponent) => {
+                format!("{}^{}", base.minimal_at(PREC_ATOM), exponent.minimal_at(PREC_NEG))
+            }
+            Model::Binary(op, lhs, rhs) => format!(
+                "{} {} {}",
+                lhs.minimal_at(op.precedence()),
+                op.symbol(),
+                rhs.minimal_at(op.precedence() + 1)
+            ),
+            Model::Call(name, args) => {
+                let args: Vec<String> = args.iter().map(Model::minimal).collect();
+                format!("{}({})", name, args.join(", "))
+            }
+        }
+    }
+
+    fn minimal_at(&self, precedence: u8) -> String {
+        if self.precedence() < precedence {
+            format!("({})", self.minimal())
+        } else {
+            self.minimal()
+        }
+    }
+
+    // Wraps every compound subexpression in parentheses
+    fn parenthesized(&self) -> String {
+        match self {
+            Model::Literal(literal) => literal.to_string(),
+            Model::Neg(operand) => format!("(-{})", operand.parenthesized()),
+            Model::Binary(op, lhs, rhs) => format!("({}{}{})", lhs.parenthesized(), op.symbol(), rhs.parenthesized()),
+            Model::Call(name, args) => {
+                let args: Vec<String> = args.iter().map(Model::parenthesized).collect();
+                format!("{}({})", name, args.join(","))
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ReferenceError {
+    Error(ExpressionError),
+    // Exact result is too large to be worth computing, the crate must overflow
+    TooLarge,
+}
+
+fn literal_value(literal: &str) -> BigRational {
+    match literal {
+        "dozen" => BigRational::from_integer(12.into()),
+        "score" => BigRational::from_integer(20.into()),
+        _ => {
+            let (int_part, frac_part) = literal.split_once('.').unwrap_or((literal, ""));
+            let digits: BigInt = format!("{}{}", int_part, frac_part).parse().unwrap();
+            BigRational::new(digits, BigInt::from(10).pow(frac_part.len() as u32))
+        }
+    }
+}
+
+fn reference(model: &Model) -> Result<BigRational, ReferenceError> {
+    match model {
+        Model::Literal(literal) => Ok(literal_value(literal)),
+        Model::Neg(operand) => Ok(-reference(operand)?),
+        Model::Binary(op, lhs, rhs) => {
+            let lhs = reference(lhs)?;
+            let rhs = reference(rhs)?;
+            let division_by_zero = Err(ReferenceError::Error(ExpressionError::DivisionByZero));
+            match op {
+                Op::Add => Ok(lhs + rhs),
+                Op::Sub => Ok(lhs - rhs),
+                Op::Mul => Ok(lhs * rhs),
+                Op::Div if rhs.is_zero() => division_by_zero,
+                Op::Div => Ok(lhs / rhs),
+                Op::FloorDiv | Op::Rem if rhs.is_zero() => division_by_zero,
+                Op::FloorDiv => Ok((lhs / rhs).floor()),
+                Op::Rem => Ok(lhs.clone() - rhs.clone() * (lhs / rhs).floor()),
+                Op::Pow => {
+                    if !rhs.is_integer() {
+                        return Err(ReferenceError::Error(ExpressionError::NonIntegerExponent));
+                    }
+                    if rhs.is_negative() && lhs.is_zero() {
+                        return division_by_zero;
+                    }
+                    let trivial_base = lhs.is_zero() || lhs.abs().is_one();
+                    match rhs.to_integer().to_i32() {
+                        Some(exponent) if exponent.abs() <= 512 => Ok(lhs.pow(exponent)),
+                        _ if trivial_base => {
+                            let even = rhs.to_integer().is_even();
+                            Ok(if lhs.is_zero() || (even && lhs.is_negative()) { lhs.abs() } else { lhs })
+                        }
+                        _ => Err(ReferenceError::TooLarge),
+                    }
+                }
+            }
+        }
+        Model::Call(name, args) => {
+            let args = args.iter().map(reference).collect::<Result<Vec<_>, _>>()?;
+            Ok(match *name {
+                "abs" => args[0].abs(),
+                "min" => args.into_iter().min().unwrap(),
+                "max" => args.into_iter().max().unwrap(),
+                _ => unreachable!(),
+            })
+        }
+    }
+}
+
+fn to_big(value: Rational) -> BigRational {
+    BigRational::new(value.numer().into(), value.denom().into())
+}
+
+fn evaluate_relaxed(source: &str) -> Result<Rational, SourceError> {
+    let compiled = compile_expression(source, &RELAXED_LIMITS)?;
+    execute_bytecode(&compiled.bytecode).map_err(|e| compiled.locate(source, e))
+}
+
+fn model() -> impl Strategy<Value = Model> {
+    let leaf = prop::sample::select(LITERALS).prop_map(Model::Literal);
+    leaf.prop_recursive(6, 48, 3, |inner| {
+        let op = prop::sample::select(vec![Op::Add, Op::Sub, Op::Mul, Op::Div, Op::FloorDiv, Op::Rem, Op::Pow]);
+        prop_oneof![
+            inner.clone().prop_map(|operand| Model::Neg(Box::new(operand))),
+            (op, inner.clone(), inner.clone()).prop_map(|(op, lhs, rhs)| Model::Binary(op, Box::new(lhs), Box::new(rhs))),
+            inner.clone().prop_map(|arg| Model::Call("abs", vec![arg])),
+            (prop::sample::select(vec!["min", "max"]), prop::collection::vec(inner, 2..4))
+                .prop_map(|(name, args)| Model::Call(name, args)),
+        ]
+    })
+}
+
+// Numerators and denominators clustered at the i128 bounds, where subtracting would overflow
+fn extreme_rational() -> impl Strategy<Value = Rational> {
+    let numerator = prop_oneof![i128::MIN..i128::MIN + 1_000, i128::MAX - 1_000..=i128::MAX, any::<i128>()];
+    let denominator = prop_oneof![Just(1i128), i128::MAX - 1_000..=i128::MAX, 1..=i128::MAX];
+    (numerator, denominator).prop_map(|(num, den)| Rational::new(num, den).unwrap())
+}
+
+fn valid_source() -> impl Strategy<Value = String> {
+    model().prop_map(|model| model.minimal())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn matches_reference_evaluator(model in model()) {
+        let source = model.minimal();
+        match (evaluate_relaxed(&source), reference(&model)) {
+            (Ok(actual), Ok(expected)) => prop_assert_eq!(to_big(actual), expected, "{}", source),
+            // 128-bit intermediates may overflow where arbitrary precision does not
+            (Err(e), _) if e.error == ExpressionError::Overflow => {}
+            (Err(e), Err(ReferenceError::Error(expected))) => prop_assert_eq!(e.error, expected, "{}", source),
+            (actual, expected) => prop_assert!(false, "{}: {:?} vs {:?}", source, actual, expected),
+        }
+    }
+
+    #[test]
+    fn ordering_matches_reference(a in extreme_rational(), b in extreme_rational()) {
+        prop_assert_eq!(a.cmp(&b), to_big(a).cmp(&to_big(b)));
+    }
+
+    #[test]
+    fn min_max_never_overflow(a in extreme_rational(), b in extreme_rational(), c in extreme_rational()) {
+        let (big_a, big_b, big_c) = (to_big(a), to_big(b), to_big(c));
+        let min = Function::Min.apply(&[a, b, c]).unwrap();
+        let max = Function::Max.apply(&[a, b, c]).unwrap();
+        prop_assert_eq!(to_big(min), big_a.clone().min(big_b.clone()).min(big_c.clone()));
+        prop_assert_eq!(to_big(max), big_a.max(big_b).max(big_c));
+    }
+
+    #[test]
+    fn parentheses_only_change_grouping(model in model()) {
+        let minimal = compile_expression(&model.minimal(), &RELAXED_LIMITS).unwrap();
+        let parenthesized = compile_expression(&model.parenthesized(), &RELAXED_LIMITS).unwrap();
+        prop_assert_eq!(minimal.bytecode, parenthesized.bytecode);
+    }
+
+    #[test]
+    fn trailing_operator_is_unexpected_end(source in valid_source(), op in prop::sample::select(vec!["+", "-", "*", "/", "//", "%", "^"])) {
+        let source = format!("{} {}", source, op);
+        let e = evaluate_relaxed(&source).unwrap_err();
+        prop_assert_eq!(e.error, ExpressionError::UnexpectedEnd);
+        prop_assert_eq!(e.offset, source.len());
+    }
+
+    #[test]
+    fn unclosed_parenthesis_is_unexpected_end(source in valid_source()) {
+        let source = format!("({}", source);
+        let e = evaluate_relaxed(&source).unwrap_err();
+        prop_assert_eq!(e.error, ExpressionError::UnexpectedEnd);
+        prop_assert_eq!(e.offset, source.len());
+    }
+
+    #[test]
+    fn invalid_character_is_located(source in valid_source(), position in any::<prop::sample::Index>(), c in prop::sample::select(vec!['$', '#', '=', '!', '€'])) {
+        let offset = position.index(source.len() + 1);
+        let source = format!("{}{}{}", &source[..offset], c, &source[offset..]);
+        let e = evaluate_relaxed(&source).unwrap_err();
+        prop_assert_eq!(e.error, ExpressionError::InvalidCharacter(c));
+        prop_assert_eq!(e.offset, offset);
+        prop_assert_eq!(e.token, c.to_string());
+    }
+
+    #[test]
+    fn adjacent_operands_are_unexpected_token(source in valid_source()) {
+        let source = format!("{} 7", source);
+        let e = evaluate_relaxed(&source).unwrap_err();
+        prop_assert_eq!(e.error, ExpressionError::UnexpectedToken);
+        prop_assert_eq!(e.offset, source.len() - 1);
+    }
+
+    #[test]
+    fn unknown_function_is_located(source in valid_source()) {
+        let e = evaluate_relaxed(&format!("frobnicate({})", source)).unwrap_err();
+        prop_assert_eq!(e.error, ExpressionError::UnknownFunction);
+        prop_assert_eq!(e.offset, 0);
+        prop_assert_eq!(e.token, "frobnicate");
+    }
+
+    #[test]
+    fn wrong_argument_count_is_located(source in valid_source()) {
+        let e = evaluate_relaxed(&format!("1 + abs({}, {})", source, source)).unwrap_err();
+        prop_assert_eq!(e.error, ExpressionError::WrongArgumentCount);
+        prop_assert_eq!(e.offset, 4);
+    }
+
+    #[test]
+    fn parser_never_panics_on_arbitrary_bytes(bytes in prop::collection::vec(any::<u8>(), 0..512)) {
+        let source = String::from_utf8_lossy(&bytes);
+        let _ = evaluate_expression(&source);
+        let _ = evaluate_relaxed(&source);
+    }
+
+    #[test]
+    fn parser_never_panics_on_expression_alphabet(source in "[0-9a-z._+*/%^(), -]{0,256}") {
+        let _ = evaluate_expression(&source);
+        let _ = evaluate_relaxed(&source);
+    }
+
+    #[test]
+    fn interpreter_never_panics_on_arbitrary_bytecode(bytes in prop::collection::vec(any::<u8>(), 0..512)) {
+        let _ = execute_bytecode(&bytes);
+        let mut versioned = vec![riddle_expression::BYTECODE_VERSION];
+        versioned.extend(bytes);
+        let _ = execute_bytecode(&versioned);
+    }
+}
+
+#[test]
+fn evaluates_known_expressions() {
+    let cases = [
+        ("1 * (3 - 4.5)", "-1.5"),
+        ("10 / 3", "10/3"),
+        ("0.1 + 0.2", "0.3"),
+        ("-2^2", "-4"),
+        ("(-2)^2", "4"),
+        ("2^3^2", "512"),
+        ("2^-2", "0.25"),
+        ("8 - 3 - 2", "3"),
+        ("64 / 4 / 2", "8"),
+        ("-7 // 2", "-4"),
+        ("-7 % 2", "1"),
+        ("17 % 5", "2"),
+        ("2 * -3", "-6"),
+        ("gcd(12, 18) + lcm(4, 6)", "18"),
+        ("sqrt(99) + factorial(5) + binomial(10, 3) + fib(10)", "304"),
+        ("max(1, 7/3, -2) - min(dozen, score)", "-29/3"),
+        ("max(10^38, -10^38)", "100000000000000000000000000000000000000"),
+        ("min(10^38, -10^38)", "-100000000000000000000000000000000000000"),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(evaluate_expression(source).unwrap().to_string(), expected, "{}", source);
+    }
+}
+
+#[test]
+fn call_arguments_fit_in_a_byte() {
+    // A token limit this high lets a call have more arguments than the bytecode can count
+    let limits = ExpressionLimits { max_depth: 8, max_tokens: 4096, max_operations: 4096 };
+    let args = |count: usize| (1..=count).map(|arg| arg.to_string()).collect::<Vec<_>>().join(", ");
+
+    let source = format!("max({})", args(255));
+    let compiled = compile_expression(&source, &limits).unwrap();
+    assert_eq!(execute_bytecode(&compiled.bytecode).unwrap().to_string(), "255");
+
+    let source = format!("1 + max({})", args(256));
+    let e = compile_expression(&source, &limits).unwrap_err();
+    assert_eq!(e.error, ExpressionError::WrongArgumentCount);
+    assert_eq!(e.offset, 4);
+    assert_eq!(e.token, "max");
+}
+
+#[test]
+fn default_limits_bound_nesting() {
+    let allowed = format!("{}1{}", "(".repeat(5), ")".repeat(5));
+    assert!(evaluate_expression(&allowed).is_ok());
+
+    let nested = format!("{}1{}", "(".repeat(6), ")".repeat(6));
+    assert_eq!(evaluate_expression(&nested).unwrap_err().error, ExpressionError::NestingTooDeep);
+
+    // Calls and signs nest like parentheses
+    let calls = format!("{}-1{}", "abs(".repeat(4), ")".repeat(4));
+    assert_eq!(evaluate_expression(&calls).unwrap().to_string(), "1");
+    let calls = format!("{}-1{}", "abs(".repeat(5), ")".repeat(5));
+    assert_eq!(evaluate_expression(&calls).unwrap_err().error, ExpressionError::NestingTooDeep);
+
+    // Operator chains build trees as deep as they are long, but don't nest
+    let chain = vec!["1"; 49].join("+");
+    assert_eq!(evaluate_expression(&chain).unwrap().to_string(), "49");
+
+    let long = vec!["1"; 65].join("+");
+    assert_eq!(evaluate_expression(&long).unwrap_err().error, ExpressionError::TooManyTokens);
+}
+
+#[test]
+fn deep_trees_compile_and_drop_without_recursing() {
+    // Far deeper than the native stack would allow one frame per level for
+    let limits = ExpressionLimits {
+        max_depth: 8,
+        max_tokens: 400_001,
+        max_operations: 200_000,
+    };
+    let chain = vec!["1"; 200_001].join("-");
+    let compiled = compile_expression(&chain, &limits).unwrap();
+    assert_eq!(execute_bytecode(&compiled.bytecode).unwrap().to_string(), "-199999");
+}
+
+#[test]
+fn parses_numeric_literals() {
+    let cases = [("18", "18"), ("+18.0", "18"), ("-.5", "-0.5"), ("36/2", "18"), ("1/3", "1/3"), ("007", "7")];
+    for (literal, expected) in cases {
+        assert_eq!(literal.parse::<Rational>().unwrap().to_string(), expected, "{}", literal);
+    }
+    for literal in ["", "+", "1e3", "1/0", " 18", "--1", "2/-1", "dozen"] {
+        assert!(literal.parse::<Rational>().is_err(), "{}", literal);
+    }
+}
@@ -0,0 +1,433 @@
+// SQLite game database. Events are the source of truth: the challenge, submission and payout
+// tables are derived from them, so a rolled back slot is undone by replaying the remaining events
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use riddle_rush::{ChallengeAccount, ChallengeKind, PayoutStrategy, SubmissionAccount};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+use riddle_client::{decode_event, ProgramEvent};
+use crate::source::Block;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS checkpoint (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    slot INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS slots (
+    slot INTEGER PRIMARY KEY,
+    blockhash TEXT NOT NULL,
+    block_time INTEGER,
+    finalized INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS events (
+    slot INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    signature TEXT NOT NULL,
+    name TEXT NOT NULL,
+    challenge TEXT NOT NULL,
+    data BLOB NOT NULL,
+    PRIMARY KEY (slot, tx_index, event_index)
+);
+CREATE INDEX IF NOT EXISTS events_challenge ON events (challenge);
+CREATE TABLE IF NOT EXISTS challenges (
+    address TEXT PRIMARY KEY,
+    id INTEGER NOT NULL,
+    setter TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    question TEXT NOT NULL,
+    mint TEXT,
+    entry_fee INTEGER NOT NULL,
+    pot INTEGER NOT NULL,
+    submission_deadline INTEGER NOT NULL,
+    answer_reveal_deadline INTEGER NOT NULL,
+    claim_deadline INTEGER NOT NULL,
+    setter_cut_bps INTEGER NOT NULL,
+    protocol_fee_bps INTEGER NOT NULL,
+    payout_strategy TEXT NOT NULL,
+    payout_weights TEXT,
+    solution TEXT,
+    submission_count INTEGER NOT NULL DEFAULT 0,
+    correct_submissions INTEGER NOT NULL DEFAULT 0,
+    revealed_count INTEGER NOT NULL DEFAULT 0,
+    total_paid_out INTEGER NOT NULL DEFAULT 0,
+    claims_count INTEGER NOT NULL DEFAULT 0,
+    closed INTEGER NOT NULL DEFAULT 0,
+    created_slot INTEGER NOT NULL,
+    signature TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS submissions (
+    address TEXT PRIMARY KEY,
+    challenge TEXT NOT NULL,
+    submitter TEXT NOT NULL,
+    encrypted_answer TEXT NOT NULL,
+    commitment_version INTEGER NOT NULL,
+    commit_index INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    revealed INTEGER NOT NULL DEFAULT 0,
+    answer TEXT,
+    answer_correct INTEGER NOT NULL DEFAULT 0,
+    claimed INTEGER NOT NULL DEFAULT 0,
+    closed INTEGER NOT NULL DEFAULT 0,
+    created_slot INTEGER NOT NULL,
+    signature TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS submissions_challenge ON submissions (challenge);
+CREATE TABLE IF NOT EXISTS payouts (
+    slot INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    signature TEXT NOT NULL,
+    challenge TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    role TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    PRIMARY KEY (slot, tx_index, event_index)
+);
+CREATE VIEW IF NOT EXISTS winners AS
+    SELECT s.challenge, c.id AS challenge_id, s.submitter, s.answer, p.amount AS payout
+    FROM submissions s
+    JOIN challenges c ON c.address = s.challenge
+    LEFT JOIN payouts p ON p.challenge = s.challenge AND p.recipient = s.submitter AND p.role = 'submitter'
+    WHERE s.answer_correct = 1;
+";
+
+// An event decoded from a block, with its position for ordering
+pub struct IndexedEvent {
+    pub tx_index: usize,
+    pub event_index: usize,
+    pub signature: String,
+    pub data: Vec<u8>,
+    pub event: ProgramEvent,
+}
+
+pub struct Database {
+    conn: Connection,
+}
+
+impl Database {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Database { conn })
+    }
+
+    pub fn checkpoint(&self) -> Result<Option<u64>> {
+        let slot: Option<i64> = self
+            .conn
+            .query_row("SELECT slot FROM checkpoint WHERE id = 1", [], |row| row.get(0))
+            .optional()?;
+        Ok(slot.map(|slot| slot as u64))
+    }
+
+    pub fn set_checkpoint(&self, slot: u64) -> Result<()> {
+        set_checkpoint(&self.conn, slot)
+    }
+
+    // Highest indexed slot known to be final, everything after it may still be rolled back
+    pub fn last_finalized_slot(&self) -> Result<Option<u64>> {
+        let slot: Option<i64> = self
+            .conn
+            .query_row("SELECT MAX(slot) FROM slots WHERE finalized = 1", [], |row| row.get(0))?;
+        Ok(slot.map(|slot| slot as u64))
+    }
+
+    // Indexed slots up to `slot` that were not final yet when they were indexed
+    pub fn unfinalized_slots(&self, up_to: u64) -> Result<Vec<(u64, String)>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT slot, blockhash FROM slots WHERE finalized = 0 AND slot <= ?1 ORDER BY slot")?;
+        let slots = statement
+            .query_map([up_to as i64], |row| Ok((row.get::<_, i64>(0)? as u64, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(slots)
+    }
+
+    pub fn indexed_slots(&self, start: u64, end: u64) -> Result<Vec<u64>> {
+        let mut statement = self.conn.prepare("SELECT slot FROM slots WHERE slot BETWEEN ?1 AND ?2 ORDER BY slot")?;
+        let slots = statement
+            .query_map([start as i64, end as i64], |row| Ok(row.get::<_, i64>(0)? as u64))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(slots)
+    }
+
+    pub fn mark_finalized(&self, up_to: u64) -> Result<()> {
+        self.conn
+            .execute("UPDATE slots SET finalized = 1 WHERE finalized = 0 AND slot <= ?1", [up_to as i64])?;
+        Ok(())
+    }
+
+    // Records the block and applies its events atomically
+    pub fn insert_block(&mut self, block: &Block, finalized: bool, events: &[IndexedEvent]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO slots (slot, blockhash, block_time, finalized) VALUES (?1, ?2, ?3, ?4)",
+            params![block.slot as i64, block.blockhash, block.block_time, finalized],
+        )?;
+        for event in events {
+            tx.execute(
+                "INSERT OR REPLACE INTO events (slot, tx_index, event_index, signature, name, challenge, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    block.slot as i64,
+                    event.tx_index as i64,
+                    event.event_index as i64,
+                    event.signature,
+                    event.event.name(),
+                    event.event.challenge().to_string(),
+                    event.data,
+                ],
+            )?;
+            apply_event(&tx, block.slot, event.tx_index, event.event_index, &event.signature, &event.event)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    // Forgets everything indexed from `slot` on, rebuilds the challenges it touched from their
+    // earlier events and moves the checkpoint back so the slots are indexed again
+    pub fn rollback_from(&mut self, slot: u64) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        let affected: Vec<String> = {
+            let mut statement = tx.prepare("SELECT DISTINCT challenge FROM events WHERE slot >= ?1")?;
+            let affected = statement
+                .query_map([slot as i64], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            affected
+        };
+        tx.execute("DELETE FROM events WHERE slot >= ?1", [slot as i64])?;
+        tx.execute("DELETE FROM slots WHERE slot >= ?1", [slot as i64])?;
+        for challenge in &affected {
+            tx.execute("DELETE FROM challenges WHERE address = ?1", [challenge])?;
+            tx.execute("DELETE FROM submissions WHERE challenge = ?1", [challenge])?;
+            tx.execute("DELETE FROM payouts WHERE challenge = ?1", [challenge])?;
+
+            let mut statement = tx.prepare(
+                "SELECT slot, tx_index, event_index, signature, data FROM events
+                 WHERE challenge = ?1 ORDER BY slot, tx_index, event_index",
+            )?;
+            let events: Vec<(i64, i64, i64, String, Vec<u8>)> = statement
+                .query_map([challenge], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+            for (event_slot, tx_index, event_index, signature, data) in events {
+                if let Some(event) = decode_event(&data) {
+                    apply_event(&tx, event_slot as u64, tx_index as usize, event_index as usize, &signature, &event)?;
+                }
+            }
+        }
+        set_checkpoint(&tx, slot.saturating_sub(1))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    // Overwrites the derived state with the account as currently stored on chain
+    pub fn sync_challenge_account(&self, address: &Pubkey, account: Option<&ChallengeAccount>) -> Result<()> {
+        match account {
+            Some(challenge) => self.conn.execute(
+                "UPDATE challenges SET pot = ?2, solution = NULLIF(?3, ''), submission_count = ?4,
+                 correct_submissions = ?5, revealed_count = ?6, total_paid_out = ?7, claims_count = ?8
+                 WHERE address = ?1",
+                params![
+                    address.to_string(),
+                    challenge.pot as i64,
+                    challenge.solution,
+                    challenge.submission_count as i64,
+                    challenge.correct_submissions as i64,
+                    challenge.revealed_count as i64,
+                    challenge.total_paid_out as i64,
+                    challenge.claims_count as i64,
+                ],
+            )?,
+            None => self
+                .conn
+                .execute("UPDATE challenges SET closed = 1 WHERE address = ?1", [address.to_string()])?,
+        };
+        Ok(())
+    }
+
+    pub fn sync_submission_account(&self, address: &Pubkey, account: Option<&SubmissionAccount>) -> Result<()> {
+        match account {
+            Some(submission) => self.conn.execute(
+                "UPDATE submissions SET revealed = ?2, answer_correct = ?3, claimed = ?4 WHERE address = ?1",
+                params![address.to_string(), submission.revealed, submission.answer_correct, submission.claimed],
+            )?,
+            None => self
+                .conn
+                .execute("UPDATE submissions SET closed = 1 WHERE address = ?1", [address.to_string()])?,
+        };
+        Ok(())
+    }
+}
+
+fn set_checkpoint(conn: &Connection, slot: u64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO checkpoint (id, slot) VALUES (1, ?1) ON CONFLICT (id) DO UPDATE SET slot = excluded.slot",
+        [slot as i64],
+    )?;
+    Ok(())
+}
+
+// Mirrors the challenge's paid-out tracking, `claim` for prizes and refunds paid to players
+fn count_payout(tx: &Transaction, challenge: &Pubkey, amount: u64, claim: bool) -> Result<()> {
+    tx.execute(
+        "UPDATE challenges SET total_paid_out = total_paid_out + ?2, claims_count = claims_count + ?3 WHERE address = ?1",
+        params![challenge.to_string(), amount as i64, claim as i64],
+    )?;
+    Ok(())
+}
+
+fn insert_payout(
+    tx: &Transaction,
+    position: (u64, usize, usize),
+    signature: &str,
+    challenge: &Pubkey,
+    recipient: &Pubkey,
+    role: &str,
+    amount: u64,
+) -> Result<()> {
+    let (slot, tx_index, event_index) = position;
+    tx.execute(
+        "INSERT OR REPLACE INTO payouts (slot, tx_index, event_index, signature, challenge, recipient, role, amount)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            slot as i64,
+            tx_index as i64,
+            event_index as i64,
+            signature,
+            challenge.to_string(),
+            recipient.to_string(),
+            role,
+            amount as i64,
+        ],
+    )?;
+    Ok(())
+}
+
+fn apply_event(
+    tx: &Transaction,
+    slot: u64,
+    tx_index: usize,
+    event_index: usize,
+    signature: &str,
+    event: &ProgramEvent,
+) -> Result<()> {
+    let position = (slot, tx_index, event_index);
+    match event {
+        ProgramEvent::ChallengeCreated(e) => {
+            let kind = match e.kind {
+                ChallengeKind::Expression => "expression",
+                ChallengeKind::TextRiddle => "text_riddle",
+            };
+            let (payout_strategy, payout_weights) = match &e.payout_strategy {
+                PayoutStrategy::EqualSplit => ("equal_split", None),
+                PayoutStrategy::WinnerTakesAll => ("winner_takes_all", None),
+                PayoutStrategy::TopK { weights } => {
+                    let weights = weights.iter().map(u16::to_string).collect::<Vec<_>>().join(",");
+                    ("top_k", Some(weights))
+                },
+                PayoutStrategy::Lottery { .. } => ("lottery", None),
+            };
+            tx.execute(
+                "INSERT OR REPLACE INTO challenges (address, id, setter, kind, question, mint, entry_fee, pot,
+                 submission_deadline, answer_reveal_deadline, claim_deadline, setter_cut_bps, protocol_fee_bps,
+                 payout_strategy, payout_weights, created_slot, signature)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+                params![
+                    e.challenge.to_string(),
+                    e.challenge_id as i64,
+                    e.setter.to_string(),
+                    kind,
+                    e.question,
+                    e.mint.map(|mint| mint.to_string()),
+                    e.entry_fee as i64,
+                    e.pot as i64,
+                    e.submission_deadline,
+                    e.answer_reveal_deadline,
+                    e.claim_deadline,
+                    e.setter_cut_bps,
+                    e.protocol_fee_bps,
+                    payout_strategy,
+                    payout_weights,
+                    slot as i64,
+                    signature,
+                ],
+            )?;
+        },
+        ProgramEvent::SubmissionCreated(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO submissions (address, challenge, submitter, encrypted_answer,
+                 commitment_version, commit_index, amount, created_slot, signature)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    e.submission.to_string(),
+                    e.challenge.to_string(),
+                    e.submitter.to_string(),
+                    hex(&e.encrypted_answer),
+                    e.commitment_version,
+                    e.commit_index as i64,
+                    e.amount as i64,
+                    slot as i64,
+                    signature,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE challenges SET pot = ?2, submission_count = submission_count + 1 WHERE address = ?1",
+                params![e.challenge.to_string(), e.pot as i64],
+            )?;
+        },
+        ProgramEvent::SolutionRevealed(e) => {
+            tx.execute(
+                "UPDATE challenges SET solution = ?2 WHERE address = ?1",
+                params![e.challenge.to_string(), e.solution],
+            )?;
+        },
+        ProgramEvent::SubmissionRevealed(e) => {
+            tx.execute(
+                "UPDATE submissions SET revealed = 1, answer = ?2, answer_correct = ?3 WHERE address = ?1",
+                params![e.submission.to_string(), e.answer, e.answer_correct],
+            )?;
+            tx.execute(
+                "UPDATE challenges SET correct_submissions = ?2, revealed_count = revealed_count + 1 WHERE address = ?1",
+                params![e.challenge.to_string(), e.correct_submissions as i64],
+            )?;
+        },
+        ProgramEvent::SetterClaimed(e) => {
+            insert_payout(tx, position, signature, &e.challenge, &e.setter, "setter", e.amount)?;
+            count_payout(tx, &e.challenge, e.amount, false)?;
+        },
+        ProgramEvent::SubmitterClaimed(e) => {
+            insert_payout(tx, position, signature, &e.challenge, &e.submitter, "submitter", e.amount)?;
+            count_payout(tx, &e.challenge, e.amount, true)?;
+            // Claiming closes the submission and returns its rent
+            tx.execute("UPDATE submissions SET claimed = 1, closed = 1 WHERE address = ?1", [e.submission.to_string()])?;
+        },
+        ProgramEvent::TreasuryClaimed(e) => {
+            insert_payout(tx, position, signature, &e.challenge, &e.treasury, "treasury", e.amount)?;
+            count_payout(tx, &e.challenge, e.amount, false)?;
+        },
+        ProgramEvent::ChallengeClosed(e) => {
+            // Token challenges return the vault balance, lamport challenges their own balance
+            let mint: Option<Option<String>> = tx
+                .query_row("SELECT mint FROM challenges WHERE address = ?1", [e.challenge.to_string()], |row| row.get(0))
+                .optional()?;
+            let amount = if matches!(mint, Some(Some(_))) { e.tokens } else { e.lamports };
+            insert_payout(tx, position, signature, &e.challenge, &e.setter, "close", amount)?;
+            tx.execute("UPDATE challenges SET closed = 1 WHERE address = ?1", [e.challenge.to_string()])?;
+        },
+        ProgramEvent::SubmissionRefunded(e) => {
+            insert_payout(tx, position, signature, &e.challenge, &e.submitter, "refund", e.amount)?;
+            count_payout(tx, &e.challenge, e.amount, true)?;
+            tx.execute("UPDATE submissions SET claimed = 1, closed = 1 WHERE address = ?1", [e.submission.to_string()])?;
+        },
+        ProgramEvent::SubmissionClosed(e) => {
+            tx.execute("UPDATE submissions SET closed = 1 WHERE address = ?1", [e.submission.to_string()])?;
+        },
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
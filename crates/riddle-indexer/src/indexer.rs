@@ -0,0 +1,141 @@
+// Follows the chain from the checkpoint to the tip and keeps the database consistent across forks
+use std::collections::{BTreeSet, HashMap};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+
+use crate::db::{Database, IndexedEvent};
+use riddle_client::{decode_account, decode_event, event_data, ProgramAccount};
+use crate::source::{Commitment, LedgerSource};
+
+pub struct Indexer<S> {
+    pub source: S,
+    pub db: Database,
+    pub program_id: Pubkey,
+    pub commitment: Commitment,
+    pub batch_size: u64, // Slots requested per getBlocks call
+}
+
+#[derive(Default)]
+struct Touched {
+    challenges: BTreeSet<Pubkey>,
+    submissions: BTreeSet<Pubkey>,
+}
+
+impl<S: LedgerSource> Indexer<S> {
+    // Starts indexing again from `slot`, dropping anything indexed after it
+    pub fn rewind_to(&mut self, slot: u64) -> Result<()> {
+        self.db.rollback_from(slot)
+    }
+
+    // Indexes every block between the checkpoint and the tip, returns the new checkpoint. A fresh
+    // database starts at `start_slot`, or at the tip when none is given
+    pub fn sync(&mut self, start_slot: Option<u64>) -> Result<u64> {
+        let finalized = self.source.slot(Commitment::Finalized)?;
+        self.check_forks(finalized)?;
+
+        let tip = self.source.slot(self.commitment)?;
+        let mut next = match self.db.checkpoint()? {
+            Some(checkpoint) => checkpoint + 1,
+            None => start_slot.unwrap_or(tip),
+        };
+        while next <= tip {
+            let end = tip.min(next + self.batch_size - 1);
+            let mut touched = Touched::default();
+            for slot in self.source.blocks(next, end, self.commitment)? {
+                let Some(block) = self.source.block(slot, self.commitment)? else {
+                    continue;
+                };
+                let mut events = Vec::new();
+                for (tx_index, transaction) in block.transactions.iter().enumerate() {
+                    if transaction.err.is_some() {
+                        continue;
+                    }
+                    for (event_index, data) in event_data(&transaction.logs, &self.program_id).into_iter().enumerate() {
+                        let Some(event) = decode_event(&data) else {
+                            continue;
+                        };
+                        touched.challenges.insert(event.challenge());
+                        touched.submissions.extend(event.submission());
+                        events.push(IndexedEvent {
+                            tx_index,
+                            event_index,
+                            signature: transaction.signature.clone(),
+                            data,
+                            event,
+                        });
+                    }
+                }
+                self.db.insert_block(&block, slot <= finalized, &events)?;
+                if !events.is_empty() {
+                    println!("slot {}: {} events", slot, events.len());
+                }
+            }
+            self.db.set_checkpoint(end)?;
+            self.sync_accounts(&touched)?;
+            next = end + 1;
+        }
+        Ok(tip.max(next.saturating_sub(1)))
+    }
+
+    // Slots indexed before they were final are checked again once they are. A slot that was
+    // dropped, replaced or skipped on the finalized chain means we followed an abandoned fork,
+    // so everything from there on is rolled back and indexed again
+    fn check_forks(&mut self, finalized: u64) -> Result<()> {
+        let pending = self.db.unfinalized_slots(finalized)?;
+        let Some(&(first_pending, _)) = pending.first() else {
+            return Ok(());
+        };
+        let start = match self.db.last_finalized_slot()? {
+            Some(slot) if slot < first_pending => slot + 1,
+            _ => first_pending,
+        };
+        let end = finalized.min(self.db.checkpoint()?.unwrap_or(finalized));
+        let canonical: BTreeSet<u64> = self.source.blocks(start, end, Commitment::Finalized)?.into_iter().collect();
+        let indexed: BTreeSet<u64> = self.db.indexed_slots(start, end)?.into_iter().collect();
+        let pending: HashMap<u64, String> = pending.into_iter().collect();
+
+        let mut fork_slot = canonical.symmetric_difference(&indexed).next().copied();
+        for slot in canonical.intersection(&indexed) {
+            if fork_slot.is_some_and(|fork_slot| fork_slot < *slot) {
+                break;
+            }
+            let Some(blockhash) = pending.get(slot) else {
+                continue;
+            };
+            if self.source.blockhash(*slot, Commitment::Finalized)?.as_ref() != Some(blockhash) {
+                fork_slot = Some(*slot);
+                break;
+            }
+        }
+
+        if let Some(slot) = fork_slot {
+            println!("slot {} is not on the finalized chain, indexing again from it", slot);
+            self.db.rollback_from(slot)?;
+        }
+        self.db.mark_finalized(finalized)
+    }
+
+    // Refreshes the touched accounts with their current on-chain state
+    fn sync_accounts(&self, touched: &Touched) -> Result<()> {
+        let challenges: Vec<Pubkey> = touched.challenges.iter().copied().collect();
+        for (address, data) in challenges.iter().zip(self.source.accounts(&challenges)?) {
+            match data.as_deref().and_then(decode_account) {
+                Some(ProgramAccount::Challenge(challenge)) => self.db.sync_challenge_account(address, Some(&challenge))?,
+                Some(_) => {},
+                None => self.db.sync_challenge_account(address, None)?,
+            }
+        }
+        let submissions: Vec<Pubkey> = touched.submissions.iter().copied().collect();
+        for (address, data) in submissions.iter().zip(self.source.accounts(&submissions)?) {
+            match data.as_deref().and_then(decode_account) {
+                Some(ProgramAccount::Submission(submission)) => {
+                    self.db.sync_submission_account(address, Some(&submission))?
+                },
+                Some(_) => {},
+                None => self.db.sync_submission_account(address, None)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+// Indexes Riddle Rush challenges, submissions, reveals and payouts into a SQLite database
+mod db;
+mod indexer;
+mod source;
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use clap::Parser;
+
+use crate::db::Database;
+use crate::indexer::Indexer;
+use crate::source::{Commitment, FixtureSource, LedgerSource, RpcSource};
+
+#[derive(Parser)]
+#[command(about = "Indexes Riddle Rush program transactions into a SQLite game database")]
+struct Args {
+    /// JSON-RPC endpoint of a validator or RPC provider
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    rpc_url: String,
+    /// Replays a ledger fixture instead of querying an RPC endpoint
+    #[arg(long)]
+    fixture: Option<PathBuf>,
+    /// SQLite database to create or update
+    #[arg(long, default_value = "riddle-rush.sqlite")]
+    database: PathBuf,
+    /// Backfills from this slot, dropping anything indexed after it. Without it the indexer resumes
+    /// from its checkpoint, or starts at the tip of a new database
+    #[arg(long)]
+    from_slot: Option<u64>,
+    /// Commitment of the indexed blocks, confirmed blocks are checked again once finalized
+    #[arg(long, value_enum, default_value = "confirmed")]
+    commitment: Commitment,
+    /// Address of the Riddle Rush program whose events are indexed
+    #[arg(long, default_value_t = riddle_rush::ID.to_string())]
+    program_id: String,
+    /// Slots requested per getBlocks call
+    #[arg(long, default_value_t = 500)]
+    batch_size: u64,
+    /// Delay between polls once the tip is reached
+    #[arg(long, default_value_t = 2000)]
+    poll_interval_ms: u64,
+    /// Stops once the tip is reached instead of following the chain
+    #[arg(long)]
+    once: bool,
+}
+
+fn run<S: LedgerSource>(args: &Args, source: S) -> Result<()> {
+    let mut indexer = Indexer {
+        source,
+        db: Database::open(&args.database)?,
+        program_id: Pubkey::from_str(&args.program_id)?,
+        commitment: args.commitment,
+        batch_size: args.batch_size.max(1),
+    };
+    if let Some(slot) = args.from_slot {
+        indexer.rewind_to(slot)?;
+    }
+    loop {
+        let slot = indexer.sync(args.from_slot)?;
+        if args.once {
+            println!("indexed up to slot {}", slot);
+            return Ok(());
+        }
+        sleep(Duration::from_millis(args.poll_interval_ms));
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    match &args.fixture {
+        Some(path) => run(&args, FixtureSource::load(path)?),
+        None => run(&args, RpcSource::new(&args.rpc_url)),
+    }
+}
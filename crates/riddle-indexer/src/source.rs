@@ -0,0 +1,230 @@
+// Where blocks and accounts come from: a JSON-RPC endpoint or a ledger fixture replayed from disk
+use std::collections::HashMap;
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Commitment {
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    fn as_str(self) -> &'static str {
+        match self {
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Block {
+    pub slot: u64,
+    pub blockhash: String,
+    #[serde(default)]
+    pub block_time: Option<i64>,
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Transaction {
+    pub signature: String,
+    #[serde(default)]
+    pub err: Option<Value>, // Failed transactions emit no events
+    #[serde(default)]
+    pub logs: Vec<String>,
+}
+
+pub trait LedgerSource {
+    fn slot(&self, commitment: Commitment) -> Result<u64>;
+    // Slots in [start, end] that produced a block, skipped slots are left out
+    fn blocks(&self, start: u64, end: u64, commitment: Commitment) -> Result<Vec<u64>>;
+    // None when the slot was skipped
+    fn block(&self, slot: u64, commitment: Commitment) -> Result<Option<Block>>;
+    fn blockhash(&self, slot: u64, commitment: Commitment) -> Result<Option<String>>;
+    // Account data in the same order as the addresses, None for missing accounts
+    fn accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>>;
+}
+
+// JSON-RPC error codes for slots without a block
+const SLOT_SKIPPED: i64 = -32007;
+const LONG_TERM_STORAGE_SLOT_SKIPPED: i64 = -32009;
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+pub struct RpcSource {
+    url: String,
+    agent: ureq::Agent,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcSource {
+    pub fn new(url: &str) -> Self {
+        RpcSource {
+            url: url.to_string(),
+            agent: ureq::agent(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<std::result::Result<Value, RpcError>> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .with_context(|| format!("{} request to {} failed", method, self.url))?
+            .into_json()?;
+        if let Some(error) = response.get("error") {
+            return Ok(Err(RpcError {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            }));
+        }
+        Ok(Ok(response["result"].clone()))
+    }
+
+    fn call_ok(&self, method: &str, params: Value) -> Result<Value> {
+        self.call(method, params)?
+            .map_err(|e| anyhow!("{} failed with {}: {}", method, e.code, e.message))
+    }
+
+    fn get_block(&self, slot: u64, commitment: Commitment, details: &str) -> Result<Option<Value>> {
+        let config = json!({
+            "encoding": "json",
+            "transactionDetails": details,
+            "rewards": false,
+            "maxSupportedTransactionVersion": 0,
+            "commitment": commitment.as_str(),
+        });
+        match self.call("getBlock", json!([slot, config]))? {
+            Ok(block) => Ok(Some(block)),
+            Err(e) if e.code == SLOT_SKIPPED || e.code == LONG_TERM_STORAGE_SLOT_SKIPPED => Ok(None),
+            Err(e) => bail!("getBlock {} failed with {}: {}", slot, e.code, e.message),
+        }
+    }
+}
+
+impl LedgerSource for RpcSource {
+    fn slot(&self, commitment: Commitment) -> Result<u64> {
+        let result = self.call_ok("getSlot", json!([{ "commitment": commitment.as_str() }]))?;
+        result.as_u64().ok_or_else(|| anyhow!("getSlot returned {}", result))
+    }
+
+    fn blocks(&self, start: u64, end: u64, commitment: Commitment) -> Result<Vec<u64>> {
+        let result = self.call_ok("getBlocks", json!([start, end, { "commitment": commitment.as_str() }]))?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    fn block(&self, slot: u64, commitment: Commitment) -> Result<Option<Block>> {
+        let Some(block) = self.get_block(slot, commitment, "full")? else {
+            return Ok(None);
+        };
+        let transactions = block["transactions"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|tx| Transaction {
+                signature: tx["transaction"]["signatures"][0].as_str().unwrap_or_default().to_string(),
+                err: Some(tx["meta"]["err"].clone()).filter(|err| !err.is_null()),
+                logs: serde_json::from_value(tx["meta"]["logMessages"].clone()).unwrap_or_default(),
+            })
+            .collect();
+        Ok(Some(Block {
+            slot,
+            blockhash: block["blockhash"].as_str().unwrap_or_default().to_string(),
+            block_time: block["blockTime"].as_i64(),
+            transactions,
+        }))
+    }
+
+    fn blockhash(&self, slot: u64, commitment: Commitment) -> Result<Option<String>> {
+        Ok(self
+            .get_block(slot, commitment, "none")?
+            .map(|block| block["blockhash"].as_str().unwrap_or_default().to_string()))
+    }
+
+    fn accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let keys: Vec<String> = chunk.iter().map(Pubkey::to_string).collect();
+            let config = json!({ "encoding": "base64", "commitment": "confirmed" });
+            let result = self.call_ok("getMultipleAccounts", json!([keys, config]))?;
+            for account in result["value"].as_array().map(Vec::as_slice).unwrap_or_default() {
+                accounts.push(match account["data"][0].as_str() {
+                    Some(data) => Some(BASE64.decode(data)?),
+                    None => None,
+                });
+            }
+        }
+        Ok(accounts)
+    }
+}
+
+// A ledger captured from a local validator, replayed to build or test a database offline
+#[derive(Debug, Deserialize)]
+pub struct FixtureSource {
+    pub confirmed_slot: u64,
+    pub finalized_slot: u64,
+    pub blocks: Vec<Block>,
+    #[serde(default)]
+    pub accounts: HashMap<String, String>, // Address to base64 account data, missing accounts count as closed
+}
+
+impl FixtureSource {
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path).with_context(|| format!("can't open fixture {}", path.display()))?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    fn tip(&self, commitment: Commitment) -> u64 {
+        match commitment {
+            Commitment::Confirmed => self.confirmed_slot,
+            Commitment::Finalized => self.finalized_slot,
+        }
+    }
+}
+
+impl LedgerSource for FixtureSource {
+    fn slot(&self, commitment: Commitment) -> Result<u64> {
+        Ok(self.tip(commitment))
+    }
+
+    fn blocks(&self, start: u64, end: u64, commitment: Commitment) -> Result<Vec<u64>> {
+        let end = end.min(self.tip(commitment));
+        Ok(self.blocks.iter().map(|block| block.slot).filter(|slot| (start..=end).contains(slot)).collect())
+    }
+
+    fn block(&self, slot: u64, commitment: Commitment) -> Result<Option<Block>> {
+        if slot > self.tip(commitment) {
+            return Ok(None);
+        }
+        Ok(self.blocks.iter().find(|block| block.slot == slot).cloned())
+    }
+
+    fn blockhash(&self, slot: u64, commitment: Commitment) -> Result<Option<String>> {
+        Ok(self.block(slot, commitment)?.map(|block| block.blockhash))
+    }
+
+    fn accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        addresses
+            .iter()
+            .map(|address| match self.accounts.get(&address.to_string()) {
+                Some(data) => Ok(Some(BASE64.decode(data)?)),
+                None => Ok(None),
+            })
+            .collect()
+    }
+}
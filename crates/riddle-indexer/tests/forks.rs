@@ -0,0 +1,144 @@
+// Replays ledger fixtures through the indexer binary: a fork indexed at `confirmed` is rolled back
+// once the other branch is finalized, and the derived tables are rebuilt from the remaining events
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Event;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use riddle_rush::{ChallengeCreated, ChallengeKind, PayoutStrategy, SubmissionCreated};
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+struct Ledger {
+    challenge: Pubkey,
+}
+
+impl Ledger {
+    fn challenge_created(&self) -> Vec<u8> {
+        ChallengeCreated {
+            challenge: self.challenge,
+            challenge_id: 0,
+            setter: Pubkey::new_unique(),
+            kind: ChallengeKind::Expression,
+            question: "6 * 7".to_string(),
+            mint: None,
+            entry_fee: 1_000,
+            pot: 1_000,
+            submission_deadline: 2_000,
+            answer_reveal_deadline: 3_000,
+            claim_deadline: 4_000,
+            setter_cut_bps: 1_000,
+            protocol_fee_bps: 100,
+            payout_strategy: PayoutStrategy::EqualSplit,
+        }
+        .data()
+    }
+
+    fn submission_created(&self, submission: Pubkey, commit_index: u64) -> Vec<u8> {
+        SubmissionCreated {
+            challenge: self.challenge,
+            challenge_id: 0,
+            submission,
+            submitter: Pubkey::new_unique(),
+            encrypted_answer: [7u8; 32],
+            commitment_version: 1,
+            commit_index,
+            amount: 1_000,
+            pot: 1_000 * (commit_index + 2),
+        }
+        .data()
+    }
+}
+
+// A block with one successful transaction emitting `events`
+fn block(slot: u64, blockhash: &str, events: &[Vec<u8>]) -> Value {
+    let program = riddle_rush::ID.to_string();
+    let mut logs = vec![format!("Program {} invoke [1]", program)];
+    logs.extend(events.iter().map(|data| format!("Program data: {}", BASE64.encode(data))));
+    logs.push(format!("Program {} success", program));
+    json!({
+        "slot": slot,
+        "blockhash": blockhash,
+        "transactions": [{ "signature": format!("signature-{}", blockhash), "logs": logs }],
+    })
+}
+
+fn index(directory: &Path, fixture: Value, from_slot: Option<u64>) {
+    let path = directory.join("ledger.json");
+    std::fs::write(&path, fixture.to_string()).unwrap();
+    let mut command = Command::new(env!("CARGO_BIN_EXE_riddle-indexer"));
+    command.arg("--fixture").arg(&path).arg("--database").arg(directory.join("index.sqlite")).arg("--once");
+    if let Some(slot) = from_slot {
+        command.arg("--from-slot").arg(slot.to_string());
+    }
+    let output = command.output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+fn strings(db: &Connection, query: &str) -> Vec<String> {
+    let mut statement = db.prepare(query).unwrap();
+    let rows = statement.query_map([], |row| row.get(0)).unwrap();
+    rows.collect::<rusqlite::Result<_>>().unwrap()
+}
+
+fn temp_directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("riddle-indexer-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+#[test]
+fn abandoned_fork_is_rolled_back() {
+    let directory = temp_directory("fork");
+    let ledger = Ledger { challenge: Pubkey::new_unique() };
+    let [orphaned, replaced, canonical] = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+
+    // Slots 11 and 12 are indexed while only confirmed
+    let created = block(10, "hash-10", &[ledger.challenge_created()]);
+    index(
+        &directory,
+        json!({
+            "confirmed_slot": 12,
+            "finalized_slot": 10,
+            "blocks": [
+                created,
+                block(11, "hash-11", &[ledger.submission_created(orphaned, 0)]),
+                block(12, "hash-12", &[ledger.submission_created(replaced, 1)]),
+            ],
+        }),
+        Some(10),
+    );
+    let db = Connection::open(directory.join("index.sqlite")).unwrap();
+    assert_eq!(strings(&db, "SELECT blockhash FROM slots ORDER BY slot"), ["hash-10", "hash-11", "hash-12"]);
+    assert_eq!(strings(&db, "SELECT address FROM submissions ORDER BY commit_index"), [orphaned.to_string(), replaced.to_string()]);
+
+    // The finalized chain skipped slot 11 and has another block at 12
+    index(
+        &directory,
+        json!({
+            "confirmed_slot": 12,
+            "finalized_slot": 12,
+            "blocks": [created, block(12, "hash-12b", &[ledger.submission_created(canonical, 0)])],
+        }),
+        None,
+    );
+    assert_eq!(strings(&db, "SELECT blockhash FROM slots ORDER BY slot"), ["hash-10", "hash-12b"]);
+    let unfinalized: i64 = db.query_row("SELECT COUNT(*) FROM slots WHERE finalized = 0", [], |row| row.get(0)).unwrap();
+    assert_eq!(unfinalized, 0);
+    assert_eq!(strings(&db, "SELECT address FROM submissions"), [canonical.to_string()]);
+    assert_eq!(strings(&db, "SELECT signature FROM events ORDER BY slot"), ["signature-hash-10", "signature-hash-12b"]);
+
+    // The challenge is rebuilt from its creation and the canonical submission only
+    let (submission_count, pot): (i64, i64) = db
+        .query_row("SELECT submission_count, pot FROM challenges WHERE address = ?1", [ledger.challenge.to_string()], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap();
+    assert_eq!((submission_count, pot), (1, 2_000));
+    let checkpoint: i64 = db.query_row("SELECT slot FROM checkpoint", [], |row| row.get(0)).unwrap();
+    assert_eq!(checkpoint, 12);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
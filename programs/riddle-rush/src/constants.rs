@@ -5,4 +5,22 @@ pub const SEED: &str = "anchor";
 pub const MAX_SUBMISSIONS: u64 = 1e4 as u64; // Maximum number of submissions per challenge
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
 pub const MAX_QUESTION_LENGTH: usize = 256; // Maximum length of the question and solution
-pub const SETTER_CUT: u64 = 10; // Percentage of the entry fee that goes to the setter
\ No newline at end of file
+pub const MAX_BYTECODE_LENGTH: usize = 2 * MAX_QUESTION_LENGTH; // Maximum length of the compiled question
+pub const MAX_EXPRESSION_DEPTH: usize = riddle_expression::DEFAULT_MAX_DEPTH; // Maximum nesting of parentheses, signs and calls
+pub const MAX_EXPRESSION_TOKENS: usize = riddle_expression::DEFAULT_MAX_TOKENS; // Maximum number of tokens in the question
+pub const MAX_EXPRESSION_OPERATIONS: usize = riddle_expression::DEFAULT_MAX_OPERATIONS; // Maximum number of operators and function calls in the question
+pub const MAX_ALTERNATES: usize = 4; // Maximum number of alternate accepted answers per challenge
+pub const MAX_ALTERNATE_LENGTH: usize = 64; // Maximum length of an alternate accepted answer
+pub const MAX_PAYOUT_RANKS: usize = 10; // Maximum number of ranked winners of a top-K payout
+pub const BPS_DENOMINATOR: u64 = 10_000; // Fees and cuts are expressed in basis points of the pot
+// Operations the admin can pause independently, bits of GlobalConfig::paused_operations
+pub const PAUSE_CREATE_CHALLENGE: u8 = 1 << 0;
+pub const PAUSE_SUBMIT: u8 = 1 << 1;
+pub const PAUSE_REVEAL: u8 = 1 << 2; // Solution and submission reveals
+pub const PAUSE_CLAIM: u8 = 1 << 3; // Setter, submitter and treasury claims
+pub const PAUSE_CLOSE: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 = PAUSE_CREATE_CHALLENGE | PAUSE_SUBMIT | PAUSE_REVEAL | PAUSE_CLAIM | PAUSE_CLOSE;
+pub const COMMITMENT_VERSION: u8 = 1; // Format of the submission commitments accepted by create_submission
+pub const COMMITMENT_DOMAIN: &[u8] = b"riddle-rush:submission:v1"; // Tag hashed first so commitments can't be reused by other protocols
+pub const SOLUTION_DOMAIN: &[u8] = b"riddle-rush:solution:v1"; // Same for text riddle solutions, so they can't pass for answers
+pub const LOTTERY_DOMAIN: &[u8] = b"riddle-rush:lottery:v1"; // And for the seed a lottery's setter commits to
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 
-use crate::MAX_QUESTION_LENGTH;
+use crate::{BPS_DENOMINATOR, PAUSE_ALL, MAX_ALTERNATES, MAX_ALTERNATE_LENGTH, MAX_BYTECODE_LENGTH, MAX_EXPRESSION_DEPTH, MAX_EXPRESSION_OPERATIONS, MAX_EXPRESSION_TOKENS, MAX_PAYOUT_RANKS, MAX_QUESTION_LENGTH};
 
+// Error codes are part of the client interface, new variants are only ever appended
 #[error_code]
 pub enum RiddleRushError {
     #[msg("The submission deadline has already passed.")]
@@ -35,7 +36,89 @@ pub enum RiddleRushError {
     #[msg("The answer doesn't match the encrypted answer.")]
     AnswerMismatch,
     #[msg("No correct submissions.")]
-    ZeroCorrectSubmissions
+    ZeroCorrectSubmissions,
+    #[msg("The compiled question is too long, max length: {MAX_BYTECODE_LENGTH}")]
+    BytecodeTooLong,
+    #[msg("The question is nested too deeply, max depth: {MAX_EXPRESSION_DEPTH}")]
+    ExpressionTooDeep,
+    #[msg("The question has too many tokens, max tokens: {MAX_EXPRESSION_TOKENS}")]
+    ExpressionTooManyTokens,
+    #[msg("The question has too many operations, max operations: {MAX_EXPRESSION_OPERATIONS}")]
+    ExpressionTooManyOperations,
+    #[msg("The question contains an invalid character.")]
+    ExpressionInvalidCharacter,
+    #[msg("The question contains an invalid number.")]
+    ExpressionInvalidNumber,
+    #[msg("The question ends unexpectedly.")]
+    ExpressionUnexpectedEnd,
+    #[msg("The question divides by zero.")]
+    ExpressionDivisionByZero,
+    #[msg("The question overflows 128-bit arithmetic.")]
+    ExpressionOverflow,
+    #[msg("Exponents in the question must be integers.")]
+    ExpressionNonIntegerExponent,
+    #[msg("The question calls an unknown function.")]
+    ExpressionUnknownFunction,
+    #[msg("The question uses an unknown constant.")]
+    ExpressionUnknownConstant,
+    #[msg("A function in the question has the wrong number of arguments.")]
+    ExpressionWrongArgumentCount,
+    #[msg("A function in the question has an invalid argument.")]
+    ExpressionInvalidArgument,
+    #[msg("The compiled question is invalid.")]
+    ExpressionInvalidBytecode,
+    #[msg("The question contains an unexpected token.")]
+    ExpressionUnexpectedToken,
+    #[msg("A text riddle needs a committed solution hash.")]
+    MissingSolutionHash,
+    #[msg("The solution doesn't match the committed solution hash.")]
+    SolutionHashMismatch,
+    #[msg("The solution must not be empty.")]
+    EmptySolution,
+    #[msg("Too many alternate answers, max alternates: {MAX_ALTERNATES}")]
+    TooManyAlternates,
+    #[msg("An alternate answer is too long, max length: {MAX_ALTERNATE_LENGTH}")]
+    AlternateTooLong,
+    #[msg("The token accounts don't match the challenge's mint.")]
+    TokenAccountsMismatch,
+    #[msg("The mint doesn't match the challenge's mint.")]
+    MintMismatch,
+    #[msg("Fees and setter cuts must be at most {BPS_DENOMINATOR} basis points in total, with the minimum setter cut below the maximum.")]
+    InvalidFeeConfig,
+    #[msg("The setter cut is outside the range allowed by the global config.")]
+    SetterCutOutOfRange,
+    #[msg("Only the program's upgrade authority can initialize the config.")]
+    NotUpgradeAuthority,
+    #[msg("The signer is not the pending authority.")]
+    NotPendingAuthority,
+    #[msg("This operation is paused.")]
+    ProgramPaused,
+    #[msg("Unknown operations in the pause mask, all operations: {PAUSE_ALL}")]
+    InvalidPauseMask,
+    #[msg("Unsupported submission commitment version.")]
+    UnsupportedCommitmentVersion,
+    #[msg("The submission can still claim a share of the pot, claim it instead of closing it.")]
+    SubmissionStillClaimable,
+    #[msg("Top-K payouts need between 1 and {MAX_PAYOUT_RANKS} weights, all above zero.")]
+    InvalidPayoutWeights,
+    #[msg("The answer is correct but doesn't win a share under the challenge's payout strategy.")]
+    NotAWinner,
+    #[msg("The solution was revealed, claim the prize or refund with submitter_claim instead.")]
+    ChallengeResolved,
+    #[msg("The solution can't be revealed after the answer reveal deadline.")]
+    SolutionRevealDeadlinePassed,
+    #[msg("Nobody answered correctly and this answer was never revealed, so it doesn't share the refund.")]
+    SubmissionNotRevealed,
+    #[msg("The payout would exceed the pot.")]
+    PayoutExceedsPot,
+    #[msg("Text riddles can't have alternates, they would be public before the solution is revealed.")]
+    AlternatesOnTextRiddle,
+    #[msg("The mint has a freeze authority or Token-2022 extensions other than a transfer fee.")]
+    UnsupportedMint,
+    #[msg("Answers can't be revealed after the answer reveal deadline.")]
+    AnswerRevealDeadlinePassed,
+    #[msg("A lottery needs the hash of the setter's seed.")]
+    MissingLotterySeedHash,
+    #[msg("The salt doesn't match the lottery's seed hash.")]
+    LotterySeedMismatch,
 }
-
-
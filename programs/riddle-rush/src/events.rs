@@ -0,0 +1,105 @@
+// Events emitted on every state transition, so indexers can follow the game without parsing logs
+use anchor_lang::prelude::*;
+
+use crate::{ChallengeKind, PayoutStrategy};
+
+#[event]
+pub struct ChallengeCreated {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub setter: Pubkey,
+    pub kind: ChallengeKind,
+    pub question: String,
+    pub mint: Option<Pubkey>,
+    pub entry_fee: u64,
+    pub pot: u64,
+    pub submission_deadline: i64,
+    pub answer_reveal_deadline: i64,
+    pub claim_deadline: i64,
+    pub setter_cut_bps: u16,
+    pub protocol_fee_bps: u16,
+    pub payout_strategy: PayoutStrategy,
+}
+
+#[event]
+pub struct SubmissionCreated {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub submission: Pubkey,
+    pub submitter: Pubkey,
+    pub encrypted_answer: [u8; 32],
+    pub commitment_version: u8,
+    pub commit_index: u64,
+    pub amount: u64, // Net of token transfer fees
+    pub pot: u64,
+}
+
+#[event]
+pub struct SolutionRevealed {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub solution: String,
+}
+
+#[event]
+pub struct SubmissionRevealed {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub submission: Pubkey,
+    pub submitter: Pubkey,
+    pub answer: String,
+    pub answer_correct: bool,
+    pub correct_submissions: u64,
+}
+
+#[event]
+pub struct SetterClaimed {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub setter: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SubmitterClaimed {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub submission: Pubkey,
+    pub submitter: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SubmissionRefunded {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub submission: Pubkey,
+    pub submitter: Pubkey,
+    pub amount: u64, // The entry fee the challenge received from the submitter
+}
+
+#[event]
+pub struct TreasuryClaimed {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub treasury: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ChallengeClosed {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub setter: Pubkey,
+    pub lamports: u64, // Remaining balance and rent returned to the setter
+    pub tokens: u64, // Remaining vault balance returned to the setter, zero for lamport challenges
+}
+
+#[event]
+pub struct SubmissionClosed {
+    pub challenge: Pubkey,
+    pub challenge_id: u64,
+    pub submission: Pubkey,
+    pub submitter: Pubkey,
+    pub lamports: u64, // Rent returned to the submitter
+}
@@ -0,0 +1,25 @@
+// The proposed admin accepts and becomes the authority of the config
+use anchor_lang::prelude::*;
+
+use crate::GlobalConfig;
+use crate::error::RiddleRushError;
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub pending_authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = global_config.pending_authority == Some(pending_authority.key()) @ RiddleRushError::NotPendingAuthority,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+pub fn handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.authority = ctx.accounts.pending_authority.key();
+    global_config.pending_authority = None;
+    msg!("New authority: {}", global_config.authority);
+    Ok(())
+}
@@ -1,7 +1,7 @@
 // The creator of the challenge reveals the solution to the challenge
 use anchor_lang::prelude::*;
 
-use crate::{ChallengeAccount, evaluate_expression};
+use crate::{lottery_seed_commitment, solution_commitment, ChallengeAccount, SolutionRevealed, ChallengeKind, GlobalConfig, PayoutStrategy, PAUSE_REVEAL, execute_bytecode, locate_execution_error, report_expression_error, MAX_QUESTION_LENGTH};
 use crate::error::RiddleRushError;
 
 #[derive(Accounts)]
@@ -9,6 +9,11 @@ use crate::error::RiddleRushError;
 pub struct ChallengeSolutionReveal<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
     #[account(
         mut,
         seeds = [b"challenge", id.to_le_bytes().as_ref()],
@@ -18,21 +23,49 @@ pub struct ChallengeSolutionReveal<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<ChallengeSolutionReveal>, _id: u64) -> Result<()> {
+// The plaintext solution is only used by text riddles, expressions are evaluated on-chain. The salt opens
+// the solution hash of a text riddle and the seed hash of a lottery
+pub fn handler(ctx: Context<ChallengeSolutionReveal>, _id: u64, plaintext_solution: String, salt: [u8; 32]) -> Result<()> {
     let challenge = &mut ctx.accounts.challenge_account;
+    ctx.accounts.global_config.require_not_paused(PAUSE_REVEAL)?;
     require!(Clock::get()?.unix_timestamp > challenge.submission_deadline, RiddleRushError::SolutionRevealDeadlineNotMet);
 
-    if challenge.solution == "" {
-        match evaluate_expression(&challenge.question) {
-            Ok(result) => {
-                challenge.solution = result.to_string();
-                msg!("Solution: {}", challenge.solution);
+    if challenge.solution.is_empty() {
+        // Past this deadline the challenge is unresolved and the entry fees are refunded instead
+        require!(
+            Clock::get()?.unix_timestamp <= challenge.answer_reveal_deadline,
+            RiddleRushError::SolutionRevealDeadlinePassed
+        );
+        match challenge.kind {
+            ChallengeKind::Expression => match execute_bytecode(&challenge.bytecode) {
+                Ok(result) => {
+                    challenge.solution = result.to_string();
+                },
+                Err(e) => {
+                    return Err(report_expression_error(locate_execution_error(&challenge.question, e)));
+                }
             },
-            Err(e) => {
-                msg!("Error evaluating expression: {:?}", e);
-                return err!(RiddleRushError::InvalidExpression);
-            }
+            ChallengeKind::TextRiddle => {
+                require!(!plaintext_solution.is_empty(), RiddleRushError::EmptySolution);
+                require!(plaintext_solution.len() <= MAX_QUESTION_LENGTH, RiddleRushError::SolutionTooLong);
+                require!(
+                    solution_commitment(&challenge.key(), &plaintext_solution, &salt) == challenge.solution_hash,
+                    RiddleRushError::SolutionHashMismatch
+                );
+                challenge.solution = plaintext_solution;
+            },
+        }
+        // Players' salts are mixed in as they reveal correct answers
+        if let PayoutStrategy::Lottery { seed_hash } = challenge.payout_strategy {
+            require!(lottery_seed_commitment(&challenge.key(), &salt) == seed_hash, RiddleRushError::LotterySeedMismatch);
+            challenge.lottery_seed = salt;
         }
+        msg!("Solution: {}", challenge.solution);
+        emit!(SolutionRevealed {
+            challenge: challenge.key(),
+            challenge_id: challenge.id,
+            solution: challenge.solution.clone(),
+        });
     }
 
     Ok(())
@@ -0,0 +1,61 @@
+// The player closes a submission that can't claim anything anymore and recovers its rent
+use anchor_lang::prelude::*;
+
+use crate::{ChallengeAccount, GlobalConfig, SubmissionAccount, SubmissionClosed, PAUSE_CLOSE};
+use crate::error::RiddleRushError;
+
+#[derive(Accounts)]
+pub struct CloseSubmission<'info> {
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    #[account(
+        seeds = [b"challenge", challenge_account.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub challenge_account: Account<'info, ChallengeAccount>,
+    #[account(
+        mut,
+        close = submitter,
+        has_one = submitter,
+        constraint = submission_account.challenge_id == challenge_account.id,
+        seeds = [b"submission", challenge_account.key().as_ref(), submitter.key().as_ref()],
+        bump,
+    )]
+    pub submission_account: Account<'info, SubmissionAccount>,
+}
+
+pub fn handler(
+    ctx: Context<CloseSubmission>,
+) -> Result<()> {
+    ctx.accounts.global_config.require_not_paused(PAUSE_CLOSE)?;
+    let challenge = &ctx.accounts.challenge_account;
+    let submission = &ctx.accounts.submission_account;
+
+    // Answers are only settled once the reveal window is over
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time > challenge.answer_reveal_deadline,
+        RiddleRushError::WithdrawTooEarly
+    );
+
+    // Submissions with a prize or refund to claim close through submitter_claim or refund until the claim deadline
+    let claimable = current_time < challenge.claim_deadline
+        && (challenge.solution.is_empty() || challenge.submitter_share(submission).is_ok());
+    require!(!claimable, RiddleRushError::SubmissionStillClaimable);
+
+    let lamports = submission.to_account_info().lamports();
+    msg!("Closed submission of {} to challenge {} and returned {} lamports", submission.submitter, challenge.id, lamports);
+    emit!(SubmissionClosed {
+        challenge: challenge.key(),
+        challenge_id: challenge.id,
+        submission: submission.key(),
+        submitter: submission.submitter,
+        lamports,
+    });
+    Ok(())
+}
@@ -0,0 +1,47 @@
+// Hash commitments used to hide answers and text riddle solutions until they are revealed
+use anchor_lang::prelude::Pubkey;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{COMMITMENT_DOMAIN, LOTTERY_DOMAIN, SOLUTION_DOMAIN};
+
+// Text riddle solution committed by the setter, laid out like a submission commitment so the setter
+// can't move bytes between the solution and the salt to open it as a different solution
+pub fn solution_commitment(challenge: &Pubkey, solution: &str, salt: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut hash_output = [0u8; 32];
+    hasher.update(SOLUTION_DOMAIN);
+    hasher.update(challenge.as_ref());
+    hasher.update(&(solution.len() as u32).to_le_bytes());
+    hasher.update(solution.as_bytes());
+    hasher.update(salt);
+    hasher.finalize(&mut hash_output);
+    hash_output
+}
+
+// Version 1 submission commitment. Binding the challenge and submitter stops a commitment from being
+// copied into another challenge or by another player, and the length prefix and fixed-size salt make
+// the split between answer and salt unambiguous
+pub fn submission_commitment(challenge: &Pubkey, submitter: &Pubkey, answer: &str, salt: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut hash_output = [0u8; 32];
+    hasher.update(COMMITMENT_DOMAIN);
+    hasher.update(challenge.as_ref());
+    hasher.update(submitter.as_ref());
+    hasher.update(&(answer.len() as u32).to_le_bytes());
+    hasher.update(answer.as_bytes());
+    hasher.update(salt);
+    hasher.finalize(&mut hash_output);
+    hash_output
+}
+
+// Seed hash committed by the setter of a lottery. The seed stays hidden until the solution is revealed,
+// so players can't pick their salts to steer the draw
+pub fn lottery_seed_commitment(challenge: &Pubkey, seed: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut hash_output = [0u8; 32];
+    hasher.update(LOTTERY_DOMAIN);
+    hasher.update(challenge.as_ref());
+    hasher.update(seed);
+    hasher.finalize(&mut hash_output);
+    hash_output
+}
@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{ChallengeAccount, GlobalConfig, ANCHOR_DISCRIMINATOR, expression::evaluate_expression};
+use crate::{AnswerNormalization, ChallengeAccount, ChallengeKind, GlobalConfig, PayoutStrategy, ANCHOR_DISCRIMINATOR, MAX_ALTERNATES, MAX_ALTERNATE_LENGTH, MAX_BYTECODE_LENGTH, MAX_QUESTION_LENGTH, PAUSE_CREATE_CHALLENGE};
+use crate::{require_supported_mint, token_accounts, ChallengeCreated};
+use crate::expression::{compile_expression, execute_bytecode, expression_limits, report_expression_error};
 use crate::error::RiddleRushError;
 
 #[derive(Accounts)]
@@ -23,8 +26,24 @@ pub struct CreateChallenge<'info> {
     )]
     pub challenge_account: Box<Account<'info, ChallengeAccount>>,
     pub system_program: Program<'info, System>,
+    // Token accounts, only passed when the entry fee is paid in an SPL token
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        payer = setter,
+        seeds = [b"vault", challenge_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = challenge_account,
+        token::token_program = token_program,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub setter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<CreateChallenge>,
     question: String,
@@ -32,17 +51,53 @@ pub fn handler(
     answer_reveal_deadline: i64,   
     claim_deadline: i64,
     entry_fee: u64, 
+    kind: ChallengeKind,
+    solution_hash: [u8; 32],
+    normalization: AnswerNormalization,
+    setter_cut_bps: u16,
+    payout_strategy: PayoutStrategy,
 ) -> Result<()> {
-    // Check if the solution is a valid mathematical expression
-    match evaluate_expression(&question) {
-        Ok(result) => {
-            msg!("Expression result: {}", result);
+    let global_config = &ctx.accounts.global_config;
+    global_config.require_not_paused(PAUSE_CREATE_CHALLENGE)?;
+    require!(question.len() <= MAX_QUESTION_LENGTH, RiddleRushError::QuestionTooLong);
+    require!(
+        (global_config.min_setter_cut_bps..=global_config.max_setter_cut_bps).contains(&setter_cut_bps),
+        RiddleRushError::SetterCutOutOfRange
+    );
+    require!(normalization.alternates.len() <= MAX_ALTERNATES, RiddleRushError::TooManyAlternates);
+    require!(
+        normalization.alternates.iter().all(|alternate| alternate.len() <= MAX_ALTERNATE_LENGTH),
+        RiddleRushError::AlternateTooLong
+    );
+    payout_strategy.validate()?;
+
+    let bytecode = match kind {
+        ChallengeKind::Expression => {
+            // Compile the question and run it once to check it is a valid mathematical expression
+            let compiled = compile_expression(&question, &expression_limits()).map_err(report_expression_error)?;
+            match execute_bytecode(&compiled.bytecode) {
+                Ok(result) => {
+                    msg!("Expression result: {}", result);
+                },
+                Err(e) => {
+                    return Err(report_expression_error(compiled.locate(&question, e)));
+                }
+            }
+            require!(compiled.bytecode.len() <= MAX_BYTECODE_LENGTH, RiddleRushError::BytecodeTooLong);
+            compiled.bytecode
         },
-        Err(e) => {
-            msg!("Error evaluating expression: {:?}", e);
-            return err!(RiddleRushError::InvalidExpression);
-        }
-    }
+        ChallengeKind::TextRiddle => {
+            // The solution stays hidden until the setter reveals it, only its hash is stored
+            require!(solution_hash != [0u8; 32], RiddleRushError::MissingSolutionHash);
+            // Alternates are stored in the clear, so they would give the answer away before the reveal
+            require!(normalization.alternates.is_empty(), RiddleRushError::AlternatesOnTextRiddle);
+            Vec::new()
+        },
+    };
+    let solution_hash = match kind {
+        ChallengeKind::Expression => [0u8; 32],
+        ChallengeKind::TextRiddle => solution_hash,
+    };
 
     require!(
         submission_deadline < answer_reveal_deadline,
@@ -59,33 +114,85 @@ pub fn handler(
         RiddleRushError::SubmissionDeadlinePassed
     );
 
-    // Transfer entry fee from setter to challenge account
-    let cpi_context = CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        anchor_lang::system_program::Transfer {
-            from: ctx.accounts.setter.to_account_info(),
-            to: ctx.accounts.challenge_account.to_account_info(),
+    if let Some(mint) = &ctx.accounts.mint {
+        require_supported_mint(mint)?;
+    }
+
+    // Transfer entry fee from setter to the vault, or to the challenge account when paid in lamports
+    let mint = ctx.accounts.mint.as_ref().map(|mint| mint.key());
+    let tokens = token_accounts(
+        mint,
+        &ctx.accounts.mint,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.setter_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    let pot = match tokens {
+        Some(mut tokens) => tokens.deposit(ctx.accounts.setter.to_account_info(), entry_fee)?,
+        None => {
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.setter.to_account_info(),
+                    to: ctx.accounts.challenge_account.to_account_info(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, entry_fee)?;
+            entry_fee
         },
-    );
-    anchor_lang::system_program::transfer(cpi_context, entry_fee)?;
-    
+    };
+    require!(pot > 0, RiddleRushError::ZeroEntryFee);
+
     ctx.accounts.challenge_account.set_inner( 
         ChallengeAccount {
             id: ctx.accounts.global_config.next_challenge_id,
+            kind,
             question,
+            bytecode,
             solution: "".to_string(),
+            solution_hash,
+            normalization,
             submission_deadline,
             answer_reveal_deadline,
             claim_deadline,
             entry_fee,
+            mint,
             setter: ctx.accounts.setter.key(),
-            pot: entry_fee, // Initialize pot with entry fee
+            pot, // Initialize pot with entry fee
+            setter_cut_bps,
             setter_cut_claimed: false,
+            protocol_fee_bps: ctx.accounts.global_config.protocol_fee_bps,
+            protocol_fee_claimed: false,
             bump: ctx.bumps.challenge_account,
             correct_submissions: 0,
+            submission_count: 0,
+            payout_strategy,
+            ranked_submissions: Vec::new(),
+            lottery_seed: [0u8; 32],
+            total_paid_out: 0,
+            claims_count: 0,
+            revealed_count: 0,
         },
     );
 
+    let challenge = &ctx.accounts.challenge_account;
+    emit!(ChallengeCreated {
+        challenge: challenge.key(),
+        challenge_id: challenge.id,
+        setter: challenge.setter,
+        kind: challenge.kind,
+        question: challenge.question.clone(),
+        mint: challenge.mint,
+        entry_fee: challenge.entry_fee,
+        pot: challenge.pot,
+        submission_deadline: challenge.submission_deadline,
+        answer_reveal_deadline: challenge.answer_reveal_deadline,
+        claim_deadline: challenge.claim_deadline,
+        setter_cut_bps: challenge.setter_cut_bps,
+        protocol_fee_bps: challenge.protocol_fee_bps,
+        payout_strategy: challenge.payout_strategy.clone(),
+    });
+
     // Increment the next challenge ID
     ctx.accounts.global_config.next_challenge_id += 1;
 
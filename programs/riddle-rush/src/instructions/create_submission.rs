@@ -1,12 +1,18 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{SubmissionAccount, ANCHOR_DISCRIMINATOR, ChallengeAccount};
+use crate::{token_accounts, GlobalConfig, SubmissionCreated, SubmissionAccount, ANCHOR_DISCRIMINATOR, ChallengeAccount, COMMITMENT_VERSION, PAUSE_SUBMIT};
 use crate::error::RiddleRushError;
 
 #[derive(Accounts)]
 pub struct CreateSubmission<'info> {
     #[account(mut)]
     pub submitter: Signer<'info>,
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
     #[account(mut,
         seeds = [b"challenge", challenge_account.id.to_le_bytes().as_ref()],
         constraint = challenge_account.pot > 0, //checks that the challenge id was initialized
@@ -22,48 +28,90 @@ pub struct CreateSubmission<'info> {
     )]
     pub submission_account: Account<'info, SubmissionAccount>,
     pub system_program: Program<'info, System>,
+    // Token accounts, only passed when the challenge's entry fee is paid in an SPL token
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"vault", challenge_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub submitter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 pub fn handler(
     ctx: Context<CreateSubmission>,
     _encrypted_answer: [u8; 32],
+    commitment_version: u8,
 ) -> Result<()> {
+    ctx.accounts.global_config.require_not_paused(PAUSE_SUBMIT)?;
+    require!(commitment_version == COMMITMENT_VERSION, RiddleRushError::UnsupportedCommitmentVersion);
     require!(
         ctx.accounts.challenge_account.submission_deadline > Clock::get()?.unix_timestamp,
         RiddleRushError::SubmissionDeadlinePassed
     );
 
-    let submitter_info = &mut ctx.accounts.submitter.to_account_info();
-
-    // Ensure the submitter has enough lamports
-    require!(
-        submitter_info.lamports() >= ctx.accounts.challenge_account.entry_fee,
-        RiddleRushError::InsufficientFunds
-    );
-
     ctx.accounts.submission_account.set_inner(
         SubmissionAccount {
             challenge_id: ctx.accounts.challenge_account.id,
             submitter: ctx.accounts.submitter.key(),
             encrypted_answer: _encrypted_answer,
+            commitment_version,
             revealed: false,
             answer_correct: false,
             claimed: false,
+            commit_index: ctx.accounts.challenge_account.submission_count,
+            correct_index: 0,
+            amount: 0,
         }
     );
 
-    // Transfer entry fee from setter to challenge account
-    let cpi_context = CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        anchor_lang::system_program::Transfer {
-            from: ctx.accounts.submitter.to_account_info(),
-            to: ctx.accounts.challenge_account.to_account_info(),
+    // Transfer entry fee from submitter to the vault, or to the challenge account when paid in lamports
+    let entry_fee = ctx.accounts.challenge_account.entry_fee;
+    let tokens = token_accounts(
+        ctx.accounts.challenge_account.mint,
+        &ctx.accounts.mint,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.submitter_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    let received = match tokens {
+        Some(mut tokens) => tokens.deposit(ctx.accounts.submitter.to_account_info(), entry_fee)?,
+        None => {
+            // Ensure the submitter has enough lamports
+            require!(
+                ctx.accounts.submitter.lamports() >= entry_fee,
+                RiddleRushError::InsufficientFunds
+            );
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.submitter.to_account_info(),
+                    to: ctx.accounts.challenge_account.to_account_info(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, entry_fee)?;
+            entry_fee
         },
-    );
-    let _entry_fee = ctx.accounts.challenge_account.entry_fee;
-    anchor_lang::system_program::transfer(cpi_context, _entry_fee)?;
+    };
+
+    ctx.accounts.submission_account.amount = received;
+    ctx.accounts.challenge_account.pot += received;
+    ctx.accounts.challenge_account.submission_count += 1;
 
-    ctx.accounts.challenge_account.pot += ctx.accounts.challenge_account.entry_fee;
+    emit!(SubmissionCreated {
+        challenge: ctx.accounts.challenge_account.key(),
+        challenge_id: ctx.accounts.challenge_account.id,
+        submission: ctx.accounts.submission_account.key(),
+        submitter: ctx.accounts.submitter.key(),
+        encrypted_answer: ctx.accounts.submission_account.encrypted_answer,
+        commitment_version,
+        commit_index: ctx.accounts.submission_account.commit_index,
+        amount: received,
+        pot: ctx.accounts.challenge_account.pot,
+    });
 
     Ok(())
 }
\ No newline at end of file
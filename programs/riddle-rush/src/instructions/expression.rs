@@ -1,97 +1,42 @@
+// Adapter between the shared expression crate and the program's error handling
 use anchor_lang::prelude::*;
 
-#[derive(Debug)]
-pub enum ExpressionError {
-    InvalidCharacter(char),
-    InvalidNumber,
-    UnexpectedEnd,
-    DivisionByZero,
-}
+use crate::error::RiddleRushError;
+
+pub use riddle_expression::{
+    compile_expression, execute_bytecode, locate_execution_error, CompiledExpression, ExecutionError,
+    ExpressionError, ExpressionLimits, Rational, SourceError,
+};
 
-impl From<ExpressionError> for anchor_lang::error::Error {
+impl From<ExpressionError> for RiddleRushError {
     fn from(e: ExpressionError) -> Self {
         match e {
-            ExpressionError::InvalidCharacter(_c) => anchor_lang::error::Error::from(ProgramError::Custom(100)),
-            ExpressionError::InvalidNumber => anchor_lang::error::Error::from(ProgramError::Custom(101)),
-            ExpressionError::UnexpectedEnd => anchor_lang::error::Error::from(ProgramError::Custom(102)),
-            ExpressionError::DivisionByZero => anchor_lang::error::Error::from(ProgramError::Custom(103)),
+            ExpressionError::InvalidCharacter(_) => RiddleRushError::ExpressionInvalidCharacter,
+            ExpressionError::InvalidNumber => RiddleRushError::ExpressionInvalidNumber,
+            ExpressionError::UnexpectedEnd => RiddleRushError::ExpressionUnexpectedEnd,
+            ExpressionError::DivisionByZero => RiddleRushError::ExpressionDivisionByZero,
+            ExpressionError::Overflow => RiddleRushError::ExpressionOverflow,
+            ExpressionError::NonIntegerExponent => RiddleRushError::ExpressionNonIntegerExponent,
+            ExpressionError::UnknownFunction => RiddleRushError::ExpressionUnknownFunction,
+            ExpressionError::UnknownConstant => RiddleRushError::ExpressionUnknownConstant,
+            ExpressionError::WrongArgumentCount => RiddleRushError::ExpressionWrongArgumentCount,
+            ExpressionError::InvalidArgument => RiddleRushError::ExpressionInvalidArgument,
+            ExpressionError::InvalidBytecode => RiddleRushError::ExpressionInvalidBytecode,
+            ExpressionError::NestingTooDeep => RiddleRushError::ExpressionTooDeep,
+            ExpressionError::TooManyTokens => RiddleRushError::ExpressionTooManyTokens,
+            ExpressionError::TooManyOperations => RiddleRushError::ExpressionTooManyOperations,
+            ExpressionError::UnexpectedToken => RiddleRushError::ExpressionUnexpectedToken,
         }
     }
 }
 
-pub fn evaluate_expression(expr: &str) -> Result<f64> {
-    // Remove all whitespace from the expression
-    let expr = expr.replace(char::is_whitespace, "");
-    let mut chars = expr.chars().peekable();
-    evaluate_expression_recursive(&mut chars)
-}
-
-fn evaluate_expression_recursive(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<f64> {
-    let mut result = evaluate_term(chars)?;
-    
-    while let Some(&c) = chars.peek() {
-        match c {
-            '+' => {
-                chars.next();
-                result += evaluate_term(chars)?;
-            }
-            '-' => {
-                chars.next();
-                result -= evaluate_term(chars)?;
-            }
-            _ => break,
-        }
-    }
-    
-    Ok(result)
+// Logs the location of the failure and converts it into the matching program error
+pub fn report_expression_error(e: SourceError) -> anchor_lang::error::Error {
+    msg!("Expression error {:?} at byte {}: {:?}", e.error, e.offset, e.token);
+    RiddleRushError::from(e.error).into()
 }
 
-fn evaluate_term(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<f64> {
-    let mut result = evaluate_factor(chars)?;
-    
-    while let Some(&c) = chars.peek() {
-        match c {
-            '*' => {
-                chars.next();
-                result *= evaluate_factor(chars)?;
-            }
-            '/' => {
-                chars.next();
-                let divisor = evaluate_factor(chars)?;
-                if divisor == 0.0 {
-                    return Err(ExpressionError::DivisionByZero.into());
-                }
-                result /= divisor;
-            }
-            _ => break,
-        }
-    }
-    
-    Ok(result)
+// The limits the program enforces, see `MAX_EXPRESSION_DEPTH` and friends
+pub fn expression_limits() -> ExpressionLimits {
+    ExpressionLimits::default()
 }
-
-fn evaluate_factor(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<f64> {
-    match chars.peek() {
-        Some(&c) if c.is_digit(10) || c == '.' => {
-            let mut num_str = String::new();
-            while let Some(&c) = chars.peek() {
-                if c.is_digit(10) || c == '.' {
-                    num_str.push(chars.next().unwrap());
-                } else {
-                    break;
-                }
-            }
-            num_str.parse::<f64>().map_err(|_| ExpressionError::InvalidNumber.into())
-        }
-        Some(&c) if c == '(' => {
-            chars.next();
-            let result = evaluate_expression_recursive(chars)?;
-            match chars.next() {
-                Some(')') => Ok(result),
-                _ => Err(ExpressionError::UnexpectedEnd.into()),
-            }
-        }
-        Some(&c) => Err(ExpressionError::InvalidCharacter(c).into()),
-        None => Err(ExpressionError::UnexpectedEnd.into()),
-    }
-} 
\ No newline at end of file
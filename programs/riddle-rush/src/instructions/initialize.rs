@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 
+use crate::program::RiddleRush;
 use crate::{GlobalConfig, ANCHOR_DISCRIMINATOR};
+use crate::error::RiddleRushError;
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
@@ -14,15 +16,33 @@ pub struct Initialize<'info> {
         bump,
     )]
     pub global_config: Account<'info, GlobalConfig>,
+    // Only the upgrade authority may initialize, so nobody can front-run the deployment
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, RiddleRush>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ RiddleRushError::NotUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Initialize>) -> Result<()> {
+pub fn handler(
+    ctx: Context<Initialize>,
+    treasury: Pubkey,
+    protocol_fee_bps: u16,
+    min_setter_cut_bps: u16,
+    max_setter_cut_bps: u16,
+) -> Result<()> {
     ctx.accounts.global_config.set_inner(
         GlobalConfig {
             next_challenge_id: 0,
             bump: ctx.bumps.global_config,
+            authority: ctx.accounts.authority.key(),
+            pending_authority: None,
+            paused_operations: 0,
+            treasury,
+            protocol_fee_bps,
+            min_setter_cut_bps,
+            max_setter_cut_bps,
         }
     );
-    Ok(())
+    ctx.accounts.global_config.validate_fees()
 } 
\ No newline at end of file
@@ -1,19 +1,43 @@
+#![allow(ambiguous_glob_reexports)]
+
 pub mod create_challenge;
+pub mod commitment;
 pub mod expression;
 pub mod create_submission;
 pub mod setter_claim;
 pub mod submitter_claim;
+pub mod treasury_claim;
 pub mod setter_close_challenge;
+pub mod close_submission;
+pub mod sweep_submission;
+pub mod refund;
 pub mod challenge_solution_reveal;
 pub mod submission_solution_reveal;
 pub mod initialize;
+pub mod update_config;
+pub mod propose_authority;
+pub mod accept_authority;
+pub mod set_paused;
+pub mod normalization;
+pub mod payout;
+pub mod vault;
 
 pub use create_challenge::*;
+pub use commitment::*;
 pub use expression::*;
 pub use create_submission::*;
 pub use setter_claim::*;
 pub use submitter_claim::*;
+pub use treasury_claim::*;
 pub use setter_close_challenge::*;
+pub use close_submission::*;
+pub use sweep_submission::*;
+pub use refund::*;
 pub use challenge_solution_reveal::*;
 pub use submission_solution_reveal::*;
 pub use initialize::*;
+pub use update_config::*;
+pub use propose_authority::*;
+pub use accept_authority::*;
+pub use set_paused::*;
+pub use vault::*;
@@ -0,0 +1,35 @@
+// Answer normalization applied before a revealed answer is compared with the solution
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{AnswerNormalization, Rational};
+
+impl AnswerNormalization {
+    pub fn normalize(&self, answer: &str) -> String {
+        let mut normalized = if self.nfkc {
+            answer.nfkc().collect::<String>()
+        } else {
+            answer.to_string()
+        };
+        if self.trim {
+            normalized = normalized.trim().to_string();
+        }
+        if self.lowercase {
+            normalized = normalized.to_lowercase();
+        }
+        if self.numeric {
+            // Anything that isn't a plain number is compared as text
+            if let Ok(value) = normalized.parse::<Rational>() {
+                normalized = value.to_string();
+            }
+        }
+        normalized
+    }
+
+    // Whether the answer matches the solution or one of the alternates once both are normalized
+    pub fn accepts(&self, solution: &str, answer: &str) -> bool {
+        let answer = self.normalize(answer);
+        std::iter::once(solution)
+            .chain(self.alternates.iter().map(String::as_str))
+            .any(|accepted| self.normalize(accepted) == answer)
+    }
+}
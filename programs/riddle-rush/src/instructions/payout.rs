@@ -0,0 +1,163 @@
+// Prize shares of correct answers under each payout strategy
+use anchor_lang::prelude::*;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{ChallengeAccount, PayoutStrategy, SubmissionAccount, MAX_PAYOUT_RANKS};
+use crate::error::RiddleRushError;
+
+impl PayoutStrategy {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            PayoutStrategy::TopK { weights } => require!(
+                !weights.is_empty() && weights.len() <= MAX_PAYOUT_RANKS && weights.iter().all(|weight| *weight > 0),
+                RiddleRushError::InvalidPayoutWeights
+            ),
+            PayoutStrategy::Lottery { seed_hash } => require!(*seed_hash != [0u8; 32], RiddleRushError::MissingLotterySeedHash),
+            PayoutStrategy::EqualSplit | PayoutStrategy::WinnerTakesAll => {},
+        }
+        Ok(())
+    }
+
+    // How many of the earliest committed correct answers the strategy ranks
+    pub fn ranks(&self) -> usize {
+        match self {
+            PayoutStrategy::WinnerTakesAll => 1,
+            PayoutStrategy::TopK { weights } => weights.len(),
+            PayoutStrategy::EqualSplit | PayoutStrategy::Lottery { .. } => 0,
+        }
+    }
+
+    // Share of `prize` for a correct answer, out of `correct` correct answers. `rank` is its position
+    // among the ranked answers and `drawn` whether it won the lottery. Shares are rounded down, so
+    // they never add up to more than the prize
+    pub fn share(&self, prize: u64, correct: u64, rank: Option<usize>, drawn: bool) -> u64 {
+        match self {
+            PayoutStrategy::EqualSplit => prize.checked_div(correct).unwrap_or(0),
+            PayoutStrategy::WinnerTakesAll => match rank {
+                Some(0) => prize,
+                _ => 0,
+            },
+            PayoutStrategy::TopK { weights } => {
+                // With fewer correct answers than ranks, the prize is shared by the ranks that were filled
+                let ranked = &weights[..weights.len().min(correct as usize)];
+                let total: u64 = ranked.iter().map(|weight| *weight as u64).sum();
+                match rank.and_then(|rank| ranked.get(rank)) {
+                    Some(weight) => (prize as u128 * *weight as u128 / total as u128) as u64,
+                    None => 0,
+                }
+            },
+            PayoutStrategy::Lottery { .. } => {
+                if drawn {
+                    prize
+                } else {
+                    0
+                }
+            },
+        }
+    }
+}
+
+impl ChallengeAccount {
+    // What is left for the players after the setter's cut and the protocol fee
+    pub fn prize(&self) -> u64 {
+        self.pot - self.setter_cut() - self.protocol_fee()
+    }
+
+    // Counts a correct reveal, ranking it by commit order and mixing its salt into the lottery seed
+    pub fn record_correct_answer(&mut self, submission: &mut SubmissionAccount, salt: &[u8; 32]) {
+        submission.correct_index = self.correct_submissions;
+        self.correct_submissions += 1;
+
+        let position = self.ranked_submissions.partition_point(|index| *index < submission.commit_index);
+        if position < self.payout_strategy.ranks() {
+            self.ranked_submissions.insert(position, submission.commit_index);
+            self.ranked_submissions.truncate(self.payout_strategy.ranks());
+        }
+
+        // XOR doesn't depend on the order of the reveals, and the salts were committed before the seed was known
+        for (seed, salt) in self.lottery_seed.iter_mut().zip(salt) {
+            *seed ^= salt;
+        }
+    }
+
+    // Correct index of the lottery winner, only final once the reveal window is over
+    pub fn lottery_draw(&self) -> u64 {
+        let mut hasher = Keccak::v256();
+        let mut draw = [0u8; 32];
+        hasher.update(&self.lottery_seed);
+        hasher.finalize(&mut draw);
+        u64::from_le_bytes(draw[..8].try_into().unwrap()).checked_rem(self.correct_submissions).unwrap_or(0)
+    }
+
+    // Prize share of a correct submission under the challenge's payout strategy
+    pub fn winner_share(&self, submission: &SubmissionAccount) -> u64 {
+        let rank = self.ranked_submissions.iter().position(|index| *index == submission.commit_index);
+        let drawn = submission.correct_index == self.lottery_draw();
+        self.payout_strategy.share(self.prize(), self.correct_submissions, rank, drawn)
+    }
+
+    // Number of claims the prize is shared by: the winners, or every revealed answer when nobody answered correctly
+    pub fn prize_claims(&self) -> u64 {
+        if self.correct_submissions == 0 {
+            return self.revealed_count;
+        }
+        match &self.payout_strategy {
+            PayoutStrategy::EqualSplit => self.correct_submissions,
+            PayoutStrategy::WinnerTakesAll | PayoutStrategy::Lottery { .. } => 1,
+            PayoutStrategy::TopK { weights } => (weights.len() as u64).min(self.correct_submissions),
+        }
+    }
+
+    // Paid out of the prize so far, i.e. everything but the setter's cut and the protocol fee
+    pub fn prize_paid_out(&self) -> u64 {
+        let mut paid = self.total_paid_out;
+        if self.setter_cut_claimed {
+            paid -= self.setter_cut();
+        }
+        if self.protocol_fee_claimed {
+            paid -= self.protocol_fee();
+        }
+        paid
+    }
+
+    // Share of the prize a submission can claim once the solution is revealed. Shares are rounded down
+    // and the last of the prize claims takes the remainder, so the whole prize is paid out
+    pub fn submitter_share(&self, submission: &SubmissionAccount) -> Result<u64> {
+        let share = if self.correct_submissions == 0 {
+            // If no players have answered correctly, every player who revealed gets an equal refund
+            require!(submission.revealed, RiddleRushError::SubmissionNotRevealed);
+            self.prize() / self.revealed_count
+        } else {
+            // If someone has answered correctly, this answer must be correct to be eligible for a prize
+            require!(submission.answer_correct, RiddleRushError::AnswerMismatch);
+            // The payout strategy decides which correct answers win and how much
+            let winner = match self.payout_strategy {
+                PayoutStrategy::EqualSplit => true,
+                PayoutStrategy::Lottery { .. } => submission.correct_index == self.lottery_draw(),
+                PayoutStrategy::WinnerTakesAll | PayoutStrategy::TopK { .. } => {
+                    self.ranked_submissions.contains(&submission.commit_index)
+                },
+            };
+            require!(winner, RiddleRushError::NotAWinner);
+            self.winner_share(submission)
+        };
+
+        if self.claims_count + 1 == self.prize_claims() {
+            self.prize()
+                .checked_sub(self.prize_paid_out())
+                .ok_or(error!(RiddleRushError::PayoutExceedsPot))
+        } else {
+            Ok(share)
+        }
+    }
+
+    // Counts a payout out of the pot, which never pays out more than it received
+    pub fn record_payout(&mut self, amount: u64) -> Result<()> {
+        self.total_paid_out = self
+            .total_paid_out
+            .checked_add(amount)
+            .filter(|paid| *paid <= self.pot)
+            .ok_or(error!(RiddleRushError::PayoutExceedsPot))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+// The admin proposes a new admin, who has to accept before taking over
+use anchor_lang::prelude::*;
+
+use crate::GlobalConfig;
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+// Proposing again replaces the pending authority, None cancels the transfer
+pub fn handler(ctx: Context<ProposeAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+    ctx.accounts.global_config.pending_authority = new_authority;
+    msg!("Pending authority: {:?}", new_authority);
+    Ok(())
+}
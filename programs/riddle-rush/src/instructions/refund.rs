@@ -0,0 +1,105 @@
+// Anyone can refund a player's entry fee when the solution was never revealed, e.g. because the
+// question fails at runtime or the setter never revealed it. The setter forfeits their cut
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{token_accounts, ChallengeAccount, GlobalConfig, SubmissionAccount, SubmissionRefunded, PAUSE_CLAIM};
+use crate::error::RiddleRushError;
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        seeds = [b"challenge", challenge_account.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub challenge_account: Account<'info, ChallengeAccount>,
+    /// CHECK: only receives the refund and the rent, must be the original submitter
+    #[account(mut, address = submission_account.submitter)]
+    pub submitter: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = submitter,
+        constraint = submission_account.challenge_id == challenge_account.id,
+        seeds = [b"submission", challenge_account.key().as_ref(), submitter.key().as_ref()],
+        bump,
+    )]
+    pub submission_account: Account<'info, SubmissionAccount>,
+    pub system_program: Program<'info, System>,
+    // Token accounts, only passed when the challenge's entry fee is paid in an SPL token
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"vault", challenge_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, constraint = submitter_token_account.owner == submission_account.submitter)]
+    pub submitter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn handler(
+    ctx: Context<Refund>,
+) -> Result<()> {
+    ctx.accounts.global_config.require_not_paused(PAUSE_CLAIM)?;
+
+    // The solution could still be revealed until the answer reveal deadline
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time > ctx.accounts.challenge_account.answer_reveal_deadline,
+        RiddleRushError::WithdrawTooEarly
+    );
+    require!(
+        current_time < ctx.accounts.challenge_account.claim_deadline,
+        RiddleRushError::WithdrawTooLate
+    );
+    require!(
+        ctx.accounts.challenge_account.solution.is_empty(),
+        RiddleRushError::ChallengeResolved
+    );
+
+    // The whole entry fee the challenge received comes back, no setter cut or protocol fee is taken
+    let amount = ctx.accounts.submission_account.amount;
+    ctx.accounts.submission_account.claimed = true;
+    ctx.accounts.challenge_account.claims_count += 1;
+    ctx.accounts.challenge_account.record_payout(amount)?;
+
+    let tokens = token_accounts(
+        ctx.accounts.challenge_account.mint,
+        &ctx.accounts.mint,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.submitter_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    if let Some(mut tokens) = tokens {
+        let challenge_account_info = ctx.accounts.challenge_account.to_account_info();
+        tokens.withdraw(&ctx.accounts.challenge_account, challenge_account_info, amount)?;
+        msg!("Refunded {} tokens to submitter", amount);
+    } else {
+        let challenge_account_info = &mut ctx.accounts.challenge_account.to_account_info();
+        let submitter_info = &mut ctx.accounts.submitter.to_account_info();
+        require!(
+            challenge_account_info.lamports() >= amount,
+            RiddleRushError::InsufficientFunds
+        );
+        **challenge_account_info.lamports.borrow_mut() -= amount;
+        **submitter_info.lamports.borrow_mut() += amount;
+        msg!("Refunded {} lamports to submitter", amount);
+    }
+
+    emit!(SubmissionRefunded {
+        challenge: ctx.accounts.challenge_account.key(),
+        challenge_id: ctx.accounts.challenge_account.id,
+        submission: ctx.accounts.submission_account.key(),
+        submitter: ctx.accounts.submission_account.submitter,
+        amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,25 @@
+// The admin pauses or resumes individual operations, e.g. reveals during an evaluator incident
+use anchor_lang::prelude::*;
+
+use crate::{GlobalConfig, PAUSE_ALL};
+use crate::error::RiddleRushError;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+// Replaces the whole mask, 0 resumes every operation
+pub fn handler(ctx: Context<SetPaused>, paused_operations: u8) -> Result<()> {
+    require!(paused_operations & !PAUSE_ALL == 0, RiddleRushError::InvalidPauseMask);
+    ctx.accounts.global_config.paused_operations = paused_operations;
+    msg!("Paused operations: {:#07b}", paused_operations);
+    Ok(())
+}
@@ -1,29 +1,48 @@
 // The creator of the challenge claims the percentage of the pot
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{ChallengeAccount, SETTER_CUT};
+use crate::{token_accounts, ChallengeAccount, GlobalConfig, SetterClaimed, PAUSE_CLAIM};
 use crate::error::RiddleRushError;
 
 #[derive(Accounts)]
 pub struct SetterClaim<'info> {
     #[account(mut)]
     pub setter: Signer<'info>,
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
     #[account(
         mut,
         has_one = setter,
         constraint = setter.key() == challenge_account.setter,
         constraint = challenge_account.pot > 0,
-        constraint = challenge_account.setter_cut_claimed == false,
+        constraint = !challenge_account.setter_cut_claimed,
         seeds = [b"challenge", challenge_account.id.to_le_bytes().as_ref()],
         bump,
     )]
     pub challenge_account: Account<'info, ChallengeAccount>,
     pub system_program: Program<'info, System>,
+    // Token accounts, only passed when the challenge's entry fee is paid in an SPL token
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"vault", challenge_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub setter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 pub fn handler(
     ctx: Context<SetterClaim>,
 ) -> Result<()> {
+    ctx.accounts.global_config.require_not_paused(PAUSE_CLAIM)?;
+
     //Current time should be after answer reveal deadline
     let current_time = Clock::get()?.unix_timestamp;
     require!(
@@ -37,27 +56,56 @@ pub fn handler(
         RiddleRushError::WithdrawTooLate
     );
 
-    // Calculate the setter's cut
-    let setter_cut = ctx.accounts.challenge_account.pot * SETTER_CUT / 100;
-    
-    // Transfer lamports from challenge_account (PDA) to setter
-    let challenge_account_info = &mut ctx.accounts.challenge_account.to_account_info();
-    let setter_info = &mut ctx.accounts.setter.to_account_info();
-
-    // Ensure the PDA has enough lamports
+    // A setter who never revealed the solution forfeits their cut, players take their entry fees back
+    // with `refund` and the setter's own entry fee only comes back with `setter_close_challenge`
     require!(
-        challenge_account_info.lamports() >= setter_cut,
-        RiddleRushError::InsufficientFunds
+        !ctx.accounts.challenge_account.solution.is_empty(),
+        RiddleRushError::SolutionNotRevealed
     );
+
+    // Calculate the setter's cut
+    let setter_cut = ctx.accounts.challenge_account.setter_cut();
+    
     // Update the challenge account
     ctx.accounts.challenge_account.setter_cut_claimed = true;
+    ctx.accounts.challenge_account.record_payout(setter_cut)?;
+
+    let tokens = token_accounts(
+        ctx.accounts.challenge_account.mint,
+        &ctx.accounts.mint,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.setter_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    if let Some(mut tokens) = tokens {
+        // Transfer tokens from the vault to the setter
+        let challenge_account_info = ctx.accounts.challenge_account.to_account_info();
+        tokens.withdraw(&ctx.accounts.challenge_account, challenge_account_info, setter_cut)?;
+        msg!("Transferred {} tokens to setter", setter_cut);
+    } else {
+        // Transfer lamports from challenge_account (PDA) to setter
+        let challenge_account_info = &mut ctx.accounts.challenge_account.to_account_info();
+        let setter_info = &mut ctx.accounts.setter.to_account_info();
+
+        // Ensure the PDA has enough lamports
+        require!(
+            challenge_account_info.lamports() >= setter_cut,
+            RiddleRushError::InsufficientFunds
+        );
 
-    // Decrease the PDA's lamport balance
-    **challenge_account_info.lamports.borrow_mut() -= setter_cut;
-    // Increase the setter's lamport balance
-    **setter_info.lamports.borrow_mut() += setter_cut;
+        // Decrease the PDA's lamport balance
+        **challenge_account_info.lamports.borrow_mut() -= setter_cut;
+        // Increase the setter's lamport balance
+        **setter_info.lamports.borrow_mut() += setter_cut;
 
-    msg!("Transferred {} lamports to setter", setter_cut);
+        msg!("Transferred {} lamports to setter", setter_cut);
+    }
 
+    emit!(SetterClaimed {
+        challenge: ctx.accounts.challenge_account.key(),
+        challenge_id: ctx.accounts.challenge_account.id,
+        setter: ctx.accounts.setter.key(),
+        amount: setter_cut,
+    });
     Ok(())
 }
@@ -1,13 +1,19 @@
 // Admin emergency withdraw on remaining funds & closes challenge account
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{ChallengeAccount};
+use crate::{token_accounts, ChallengeAccount, ChallengeClosed, GlobalConfig, PAUSE_CLOSE};
 use crate::error::RiddleRushError;
 
 #[derive(Accounts)]
 pub struct SetterClose<'info> {
     #[account(mut)]
     pub setter: Signer<'info>,
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
     #[account(
         mut,
         close = setter,
@@ -17,11 +23,25 @@ pub struct SetterClose<'info> {
     )]
     pub challenge_account: Account<'info, ChallengeAccount>,
     pub system_program: Program<'info, System>,
+    // Token accounts, only passed when the challenge's entry fee is paid in an SPL token
+    #[account(mut)] // Withheld Token-2022 transfer fees are harvested to the mint before the vault is closed
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"vault", challenge_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub setter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 pub fn handler(
     ctx: Context<SetterClose>,
 ) -> Result<()> {
+    ctx.accounts.global_config.require_not_paused(PAUSE_CLOSE)?;
+
     //Current time should be after answer reveal deadline
     let current_time = Clock::get()?.unix_timestamp;
     require!(
@@ -29,11 +49,35 @@ pub fn handler(
         RiddleRushError::WithdrawTooEarly
     );
     
+    // Return the remaining tokens to the setter and close the vault
+    let tokens = token_accounts(
+        ctx.accounts.challenge_account.mint,
+        &ctx.accounts.mint,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.setter_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    let mut tokens_returned = 0;
+    if let Some(mut tokens) = tokens {
+        let challenge_account_info = ctx.accounts.challenge_account.to_account_info();
+        let setter_info = ctx.accounts.setter.to_account_info();
+        let remaining = tokens.close(&ctx.accounts.challenge_account, challenge_account_info, setter_info)?;
+        msg!("Closed vault of challenge {} and transferred {} tokens to setter", ctx.accounts.challenge_account.id, remaining);
+        tokens_returned = remaining;
+    }
+
     // Transfer lamports from challenge_account (PDA) to setter
     let challenge_account_info = &mut ctx.accounts.challenge_account.to_account_info();
     let lamports = **challenge_account_info.lamports.borrow();
     let challenge_id = ctx.accounts.challenge_account.id;
 
-    msg!("Closed challenge account with id {} and transferred {} lamports to submitter", challenge_id, lamports);
+    msg!("Closed challenge account with id {} and transferred {} lamports to setter", challenge_id, lamports);
+    emit!(ChallengeClosed {
+        challenge: ctx.accounts.challenge_account.key(),
+        challenge_id,
+        setter: ctx.accounts.setter.key(),
+        lamports,
+        tokens: tokens_returned,
+    });
     Ok(())
 }
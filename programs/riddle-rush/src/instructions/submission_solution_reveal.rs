@@ -1,14 +1,18 @@
 // The player reveals the solution they have submitted previously for this challenge
 use anchor_lang::prelude::*;
 
-use crate::{ChallengeAccount, SubmissionAccount};
+use crate::{submission_commitment, ChallengeAccount, GlobalConfig, SubmissionAccount, SubmissionRevealed, COMMITMENT_VERSION, PAUSE_REVEAL};
 use crate::error::RiddleRushError;
-use tiny_keccak::{Hasher, Keccak};
 
 #[derive(Accounts)]
 pub struct SubmissionSolutionReveal<'info> {
     #[account(mut)]
     pub submitter: Signer<'info>,
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
     #[account(
         mut,
         seeds = [b"challenge", challenge_account.id.to_le_bytes().as_ref()],
@@ -24,33 +28,43 @@ pub struct SubmissionSolutionReveal<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<SubmissionSolutionReveal>, nonce: String, plaintext_answer: String) -> Result<()> {
+pub fn handler(ctx: Context<SubmissionSolutionReveal>, salt: [u8; 32], plaintext_answer: String) -> Result<()> {
+    ctx.accounts.global_config.require_not_paused(PAUSE_REVEAL)?;
     let challenge = &mut ctx.accounts.challenge_account;
     let submission = &mut ctx.accounts.submission_account;
 
-    require!(submission.revealed == false, RiddleRushError::SubmissionAlreadyRevealed);
-    require!(challenge.solution != "", RiddleRushError::SolutionNotRevealed);
-
-    let mut hasher = Keccak::v256();
-    let mut hash_output = [0u8; 32];
+    require!(!submission.revealed, RiddleRushError::SubmissionAlreadyRevealed);
+    require!(!challenge.solution.is_empty(), RiddleRushError::SolutionNotRevealed);
+    // Claims open after this deadline, so the reveal counts must be final by then
+    require!(
+        Clock::get()?.unix_timestamp <= challenge.answer_reveal_deadline,
+        RiddleRushError::AnswerRevealDeadlinePassed
+    );
 
-    // compute the keccak256 hash of the plaintext answer concatenated with the nonce
-    let concatenated_answer = format!("{}{}", plaintext_answer, nonce);
-    hasher.update(concatenated_answer.as_bytes());
-    hasher.finalize(&mut hash_output);
+    // recompute the commitment in the format the submission was made with
+    let hash_output = match submission.commitment_version {
+        COMMITMENT_VERSION => submission_commitment(&challenge.key(), &submission.submitter, &plaintext_answer, &salt),
+        _ => return err!(RiddleRushError::UnsupportedCommitmentVersion),
+    };
     // check if the encrypted answer in the submission matches the answer hash
-    msg!("Hash output: {:?}", hash_output);
-    msg!("Encrypted answer: {:?}", submission.encrypted_answer);
     require!(submission.encrypted_answer == hash_output, RiddleRushError::AnswerMismatch);
     submission.revealed = true;
-    // check if the answer is correct, i.e. the encrypted answer in the challenge matches the answer hash
-    if challenge.solution == plaintext_answer { 
+    challenge.revealed_count += 1;
+    // check if the answer is correct, i.e. it matches the solution or an alternate under the challenge's normalization
+    if challenge.normalization.accepts(&challenge.solution, &plaintext_answer) {
         submission.answer_correct = true;
-        challenge.correct_submissions += 1;
+        challenge.record_correct_answer(submission, &salt);
     } else {
         submission.answer_correct = false;
     }
-    msg!("Answer correct: {}", submission.answer_correct);
-    msg!("Correct submissions: {}", challenge.correct_submissions);
+    emit!(SubmissionRevealed {
+        challenge: challenge.key(),
+        challenge_id: challenge.id,
+        submission: submission.key(),
+        submitter: submission.submitter,
+        answer: plaintext_answer,
+        answer_correct: submission.answer_correct,
+        correct_submissions: challenge.correct_submissions,
+    });
     Ok(())
 }
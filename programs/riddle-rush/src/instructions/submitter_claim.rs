@@ -1,13 +1,19 @@
 // The player claims the pot if their answer is correct
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{ChallengeAccount, SETTER_CUT, SubmissionAccount};
+use crate::{token_accounts, ChallengeAccount, GlobalConfig, SubmissionAccount, SubmitterClaimed, PAUSE_CLAIM};
 use crate::error::RiddleRushError;
 
 #[derive(Accounts)]
 pub struct SubmitterClaim<'info> {
     #[account(mut)]
     pub submitter: Signer<'info>,
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
     #[account(
         mut,
         constraint = challenge_account.pot > 0,
@@ -17,20 +23,33 @@ pub struct SubmitterClaim<'info> {
     pub challenge_account: Account<'info, ChallengeAccount>,
     #[account(
         mut,
-        // close = submitter, //uncomment after verify solution PR
+        close = submitter, // The rent goes back to the player along with their share
         constraint = submission_account.submitter == submitter.key(),
         constraint = submission_account.challenge_id == challenge_account.id,
-        constraint = submission_account.claimed == false,
+        constraint = !submission_account.claimed,
         seeds = [b"submission", challenge_account.key().as_ref(), submitter.key().as_ref()],
         bump,
     )]
     pub submission_account: Account<'info, SubmissionAccount>,
     pub system_program: Program<'info, System>,
+    // Token accounts, only passed when the challenge's entry fee is paid in an SPL token
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"vault", challenge_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub submitter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 pub fn handler(
     ctx: Context<SubmitterClaim>,
 ) -> Result<()> {
+    ctx.accounts.global_config.require_not_paused(PAUSE_CLAIM)?;
+
     //Current time should be after answer reveal deadline
     let current_time = Clock::get()?.unix_timestamp;
     require!(
@@ -44,43 +63,56 @@ pub fn handler(
         RiddleRushError::WithdrawTooLate
     );
 
-    // Calculate the setter's cut
-    let setter_cut = ctx.accounts.challenge_account.pot * SETTER_CUT / 100;
+    // Unresolved challenges are refunded in full with `refund`
+    require!(
+        !ctx.accounts.challenge_account.solution.is_empty(),
+        RiddleRushError::SolutionNotRevealed
+    );
 
-    let mut num_players = ctx.accounts.challenge_account.correct_submissions;
+    let submitter_share = ctx.accounts.challenge_account.submitter_share(&ctx.accounts.submission_account)?;
+    
+    // Update the challenge account
+    ctx.accounts.submission_account.claimed = true;
+    ctx.accounts.challenge_account.claims_count += 1;
+    ctx.accounts.challenge_account.record_payout(submitter_share)?;
 
-    if num_players == 0 {
-        // If no players have answered correctly, every player gets their entry fee back
-        num_players = (ctx.accounts.challenge_account.pot / ctx.accounts.challenge_account.entry_fee) - 1;
+    let tokens = token_accounts(
+        ctx.accounts.challenge_account.mint,
+        &ctx.accounts.mint,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.submitter_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    if let Some(mut tokens) = tokens {
+        // Transfer tokens from the vault to the submitter
+        let challenge_account_info = ctx.accounts.challenge_account.to_account_info();
+        tokens.withdraw(&ctx.accounts.challenge_account, challenge_account_info, submitter_share)?;
+        msg!("Transferred {} tokens to submitter", submitter_share);
     } else {
-        //If someone has answered correctly, this answer must be correct to be eligible for a prize
+        // Transfer lamports from challenge_account (PDA) to submitter
+        let challenge_account_info = &mut ctx.accounts.challenge_account.to_account_info();
+        let submitter_info = &mut ctx.accounts.submitter.to_account_info();
+
+        // Ensure the PDA has enough lamports
         require!(
-            ctx.accounts.submission_account.answer_correct,
-            RiddleRushError::AnswerMismatch
+            challenge_account_info.lamports() >= submitter_share,
+            RiddleRushError::InsufficientFunds
         );
-    }
 
-    // Calculate the submitter's share
-    // note: no division by zero becuase the constraint for the submission ensures num_players > 0
-    let submitter_share = (ctx.accounts.challenge_account.pot - setter_cut) / num_players;
-    
-    // Transfer lamports from challenge_account (PDA) to setter
-    let challenge_account_info = &mut ctx.accounts.challenge_account.to_account_info();
-    let submitter_info = &mut ctx.accounts.submitter.to_account_info();
+        // Decrease the PDA's lamport balance
+        **challenge_account_info.lamports.borrow_mut() -= submitter_share;
+        // Increase the submitter's lamport balance
+        **submitter_info.lamports.borrow_mut() += submitter_share;
 
-    // Ensure the PDA has enough lamports
-    require!(
-        challenge_account_info.lamports() >= submitter_share,
-        RiddleRushError::InsufficientFunds
-    );
-    // Update the challenge account
-    ctx.accounts.submission_account.claimed = true;
-
-    // Decrease the PDA's lamport balance
-    **challenge_account_info.lamports.borrow_mut() -= submitter_share;
-    // Increase the setter's lamport balance
-    **submitter_info.lamports.borrow_mut() += submitter_share;
+        msg!("Transferred {} lamports to submitter", submitter_share);
+    }
 
-    msg!("Transferred {} lamports to submitter", setter_cut);
+    emit!(SubmitterClaimed {
+        challenge: ctx.accounts.challenge_account.key(),
+        challenge_id: ctx.accounts.challenge_account.id,
+        submission: ctx.accounts.submission_account.key(),
+        submitter: ctx.accounts.submitter.key(),
+        amount: submitter_share,
+    });
     Ok(())
 }
@@ -0,0 +1,59 @@
+// Anyone can close a submission left behind after the claim deadline, its rent goes back to the submitter
+use anchor_lang::prelude::*;
+
+use crate::{ChallengeAccount, GlobalConfig, SubmissionAccount, SubmissionClosed, PAUSE_CLOSE};
+use crate::error::RiddleRushError;
+
+#[derive(Accounts)]
+pub struct SweepSubmission<'info> {
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    /// CHECK: may already be closed by the setter, the claim deadline is read in the handler otherwise
+    #[account(
+        seeds = [b"challenge", submission_account.challenge_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub challenge_account: UncheckedAccount<'info>,
+    /// CHECK: only receives the rent, must be the original submitter
+    #[account(mut, address = submission_account.submitter)]
+    pub submitter: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = submitter,
+        seeds = [b"submission", challenge_account.key().as_ref(), submitter.key().as_ref()],
+        bump,
+    )]
+    pub submission_account: Account<'info, SubmissionAccount>,
+}
+
+pub fn handler(
+    ctx: Context<SweepSubmission>,
+) -> Result<()> {
+    ctx.accounts.global_config.require_not_paused(PAUSE_CLOSE)?;
+    let submission = &ctx.accounts.submission_account;
+
+    // Challenges are only closed after their claim deadline, so a closed one is always past it
+    let challenge_info = ctx.accounts.challenge_account.to_account_info();
+    if !challenge_info.data_is_empty() {
+        let challenge = ChallengeAccount::try_deserialize(&mut &challenge_info.data.borrow()[..])?;
+        require!(
+            Clock::get()?.unix_timestamp > challenge.claim_deadline,
+            RiddleRushError::WithdrawTooEarly
+        );
+    }
+
+    let lamports = submission.to_account_info().lamports();
+    msg!("Swept submission of {} to challenge {} and returned {} lamports", submission.submitter, submission.challenge_id, lamports);
+    emit!(SubmissionClosed {
+        challenge: challenge_info.key(),
+        challenge_id: submission.challenge_id,
+        submission: submission.key(),
+        submitter: submission.submitter,
+        lamports,
+    });
+    Ok(())
+}
@@ -0,0 +1,101 @@
+// Anyone can send the protocol fee of a challenge to the treasury
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{token_accounts, ChallengeAccount, GlobalConfig, TreasuryClaimed, PAUSE_CLAIM};
+use crate::error::RiddleRushError;
+
+#[derive(Accounts)]
+pub struct TreasuryClaim<'info> {
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    /// CHECK: only receives lamports, must be the configured treasury
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = challenge_account.pot > 0,
+        constraint = !challenge_account.protocol_fee_claimed,
+        seeds = [b"challenge", challenge_account.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub challenge_account: Account<'info, ChallengeAccount>,
+    pub system_program: Program<'info, System>,
+    // Token accounts, only passed when the challenge's entry fee is paid in an SPL token
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"vault", challenge_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, constraint = treasury_token_account.owner == global_config.treasury)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn handler(
+    ctx: Context<TreasuryClaim>,
+) -> Result<()> {
+    ctx.accounts.global_config.require_not_paused(PAUSE_CLAIM)?;
+
+    // Same window as the setter's claim
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time > ctx.accounts.challenge_account.answer_reveal_deadline,
+        RiddleRushError::WithdrawTooEarly
+    );
+    require!(
+        current_time < ctx.accounts.challenge_account.claim_deadline,
+        RiddleRushError::WithdrawTooLate
+    );
+
+    // No protocol fee is taken when the solution was never revealed, the entry fees go back to the
+    // players with `refund` and to the setter with `setter_close_challenge`
+    require!(
+        !ctx.accounts.challenge_account.solution.is_empty(),
+        RiddleRushError::SolutionNotRevealed
+    );
+
+    let protocol_fee = ctx.accounts.challenge_account.protocol_fee();
+    ctx.accounts.challenge_account.protocol_fee_claimed = true;
+    ctx.accounts.challenge_account.record_payout(protocol_fee)?;
+
+    let tokens = token_accounts(
+        ctx.accounts.challenge_account.mint,
+        &ctx.accounts.mint,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.treasury_token_account,
+        &ctx.accounts.token_program,
+    )?;
+    if let Some(mut tokens) = tokens {
+        // Transfer tokens from the vault to the treasury
+        let challenge_account_info = ctx.accounts.challenge_account.to_account_info();
+        tokens.withdraw(&ctx.accounts.challenge_account, challenge_account_info, protocol_fee)?;
+        msg!("Transferred {} tokens to treasury", protocol_fee);
+    } else {
+        // Transfer lamports from challenge_account (PDA) to treasury
+        let challenge_account_info = &mut ctx.accounts.challenge_account.to_account_info();
+        let treasury_info = &mut ctx.accounts.treasury.to_account_info();
+        require!(
+            challenge_account_info.lamports() >= protocol_fee,
+            RiddleRushError::InsufficientFunds
+        );
+        **challenge_account_info.lamports.borrow_mut() -= protocol_fee;
+        **treasury_info.lamports.borrow_mut() += protocol_fee;
+
+        msg!("Transferred {} lamports to treasury", protocol_fee);
+    }
+
+    emit!(TreasuryClaimed {
+        challenge: ctx.accounts.challenge_account.key(),
+        challenge_id: ctx.accounts.challenge_account.id,
+        treasury: ctx.accounts.treasury.key(),
+        amount: protocol_fee,
+    });
+    Ok(())
+}
@@ -0,0 +1,35 @@
+// The admin updates the fees and the treasury of the deployment
+use anchor_lang::prelude::*;
+
+use crate::GlobalConfig;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+// Existing challenges keep the fee and setter cut they were created with
+pub fn handler(
+    ctx: Context<UpdateConfig>,
+    treasury: Pubkey,
+    protocol_fee_bps: u16,
+    min_setter_cut_bps: u16,
+    max_setter_cut_bps: u16,
+) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.treasury = treasury;
+    global_config.protocol_fee_bps = protocol_fee_bps;
+    global_config.min_setter_cut_bps = min_setter_cut_bps;
+    global_config.max_setter_cut_bps = max_setter_cut_bps;
+    global_config.validate_fees()?;
+
+    msg!("Config updated: protocol fee {} bps, setter cut {}-{} bps", protocol_fee_bps, min_setter_cut_bps, max_setter_cut_bps);
+    Ok(())
+}
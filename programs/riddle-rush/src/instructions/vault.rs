@@ -0,0 +1,127 @@
+// Moves the funds of challenges denominated in an SPL token, held by the vault PDA
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::{self, extension::transfer_fee::TransferFeeConfig};
+use anchor_spl::token_interface::spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use anchor_spl::token_interface::{
+    self, get_mint_extension_data, harvest_withheld_tokens_to_mint, CloseAccount, HarvestWithheldTokensToMint, Mint,
+    TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::ChallengeAccount;
+use crate::error::RiddleRushError;
+
+pub struct TokenAccounts<'a, 'info> {
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub vault: &'a mut InterfaceAccount<'info, TokenAccount>,
+    pub user_token_account: &'a InterfaceAccount<'info, TokenAccount>, // Token account of the setter or submitter
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+// Checks the optional token accounts are all passed for token challenges and omitted otherwise
+pub fn token_accounts<'a, 'info>(
+    challenge_mint: Option<Pubkey>,
+    mint: &'a Option<InterfaceAccount<'info, Mint>>,
+    vault: &'a mut Option<InterfaceAccount<'info, TokenAccount>>,
+    user_token_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    token_program: &'a Option<Interface<'info, TokenInterface>>,
+) -> Result<Option<TokenAccounts<'a, 'info>>> {
+    match (challenge_mint, mint, vault, user_token_account, token_program) {
+        (None, None, None, None, None) => Ok(None),
+        (Some(challenge_mint), Some(mint), Some(vault), Some(user_token_account), Some(token_program)) => {
+            require_keys_eq!(mint.key(), challenge_mint, RiddleRushError::MintMismatch);
+            Ok(Some(TokenAccounts { mint, vault, user_token_account, token_program }))
+        },
+        _ => err!(RiddleRushError::TokenAccountsMismatch),
+    }
+}
+
+// The mint owner must not be able to move, freeze or lock the vault's tokens: a freeze authority, or
+// Token-2022 extensions such as a permanent delegate, transfer hook or non-transferable tokens, would
+// let them. Transfer fees are the only extension challenges account for
+pub fn require_supported_mint(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    require!(mint.freeze_authority.is_none(), RiddleRushError::UnsupportedMint);
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == spl_token_2022::ID {
+        let data = mint_info.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+        require!(
+            state.get_extension_types()?.iter().all(|extension| *extension == ExtensionType::TransferFeeConfig),
+            RiddleRushError::UnsupportedMint
+        );
+    }
+    Ok(())
+}
+
+impl<'info> TokenAccounts<'_, 'info> {
+    // Transfers from the user's token account into the vault and returns the amount the vault
+    // received, which is less than `amount` for Token-2022 mints with a transfer fee
+    pub fn deposit(&mut self, authority: AccountInfo<'info>, amount: u64) -> Result<u64> {
+        let balance_before = self.vault.amount;
+        let cpi_context = CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.user_token_account.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.vault.to_account_info(),
+                authority,
+            },
+        );
+        token_interface::transfer_checked(cpi_context, amount, self.mint.decimals)?;
+        self.vault.reload()?;
+        Ok(self.vault.amount - balance_before)
+    }
+
+    // Transfers from the vault to the user's token account, signed by the challenge PDA
+    pub fn withdraw(&mut self, challenge: &ChallengeAccount, challenge_info: AccountInfo<'info>, amount: u64) -> Result<()> {
+        require!(self.vault.amount >= amount, RiddleRushError::InsufficientFunds);
+        let id = challenge.id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"challenge", id.as_ref(), &[challenge.bump]]];
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.vault.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.user_token_account.to_account_info(),
+                authority: challenge_info,
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_context, amount, self.mint.decimals)?;
+        self.vault.reload()?;
+        Ok(())
+    }
+
+    // Sends the remaining balance to the user and closes the vault, refunding its rent to `destination`
+    pub fn close(&mut self, challenge: &ChallengeAccount, challenge_info: AccountInfo<'info>, destination: AccountInfo<'info>) -> Result<u64> {
+        let remaining = self.vault.amount;
+        if remaining > 0 {
+            self.withdraw(challenge, challenge_info.clone(), remaining)?;
+        }
+
+        // Token-2022 refuses to close accounts holding withheld transfer fees, move them to the mint first
+        if get_mint_extension_data::<TransferFeeConfig>(&self.mint.to_account_info()).is_ok() {
+            let cpi_context = CpiContext::new(
+                self.token_program.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: self.token_program.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                },
+            );
+            harvest_withheld_tokens_to_mint(cpi_context, vec![self.vault.to_account_info()])?;
+        }
+
+        let id = challenge.id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"challenge", id.as_ref(), &[challenge.bump]]];
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination,
+                authority: challenge_info,
+            },
+            signer_seeds,
+        );
+        token_interface::close_account(cpi_context)?;
+        Ok(remaining)
+    }
+}
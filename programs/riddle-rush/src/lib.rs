@@ -1,11 +1,13 @@
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -15,10 +17,39 @@ declare_id!("Gb4JYubKArC3R3EpQSzYwoPecYBNi2znUmtHrhLNeVsM");
 pub mod riddle_rush {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        initialize::handler(ctx)
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        treasury: Pubkey,
+        protocol_fee_bps: u16,
+        min_setter_cut_bps: u16,
+        max_setter_cut_bps: u16,
+    ) -> Result<()> {
+        initialize::handler(ctx, treasury, protocol_fee_bps, min_setter_cut_bps, max_setter_cut_bps)
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        treasury: Pubkey,
+        protocol_fee_bps: u16,
+        min_setter_cut_bps: u16,
+        max_setter_cut_bps: u16,
+    ) -> Result<()> {
+        update_config::handler(ctx, treasury, protocol_fee_bps, min_setter_cut_bps, max_setter_cut_bps)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+        propose_authority::handler(ctx, new_authority)
     }
 
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        accept_authority::handler(ctx)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused_operations: u8) -> Result<()> {
+        set_paused::handler(ctx, paused_operations)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_challenge(
         ctx: Context<CreateChallenge>,
         question: String,
@@ -26,15 +57,21 @@ pub mod riddle_rush {
         answer_reveal_deadline: i64,
         claim_deadline: i64,
         entry_fee: u64,
+        kind: ChallengeKind,
+        solution_hash: [u8; 32],
+        normalization: AnswerNormalization,
+        setter_cut_bps: u16,
+        payout_strategy: PayoutStrategy,
     ) -> Result<()> {
-        create_challenge::handler(ctx, question, submission_deadline, answer_reveal_deadline, claim_deadline, entry_fee)
+        create_challenge::handler(ctx, question, submission_deadline, answer_reveal_deadline, claim_deadline, entry_fee, kind, solution_hash, normalization, setter_cut_bps, payout_strategy)
     }
 
     pub fn create_submission(
         ctx: Context<CreateSubmission>,
         encrypted_answer: [u8; 32],
+        commitment_version: u8,
     ) -> Result<()> {
-        create_submission::handler(ctx, encrypted_answer)
+        create_submission::handler(ctx, encrypted_answer, commitment_version)
     }
     pub fn setter_claim(
         ctx: Context<SetterClaim>
@@ -48,24 +85,50 @@ pub mod riddle_rush {
         submitter_claim::handler(ctx)
     }
 
+    pub fn treasury_claim(
+        ctx: Context<TreasuryClaim>
+    ) -> Result<()> {
+        treasury_claim::handler(ctx)
+    }
+
     pub fn setter_close_challenge(
         ctx: Context<SetterClose>
     ) -> Result<()> {
         setter_close_challenge::handler(ctx)
     }
+
+    pub fn close_submission(
+        ctx: Context<CloseSubmission>
+    ) -> Result<()> {
+        close_submission::handler(ctx)
+    }
+
+    pub fn sweep_submission(
+        ctx: Context<SweepSubmission>
+    ) -> Result<()> {
+        sweep_submission::handler(ctx)
+    }
+
+    pub fn refund(
+        ctx: Context<Refund>
+    ) -> Result<()> {
+        refund::handler(ctx)
+    }
     
     pub fn challenge_solution_reveal(
         ctx: Context<ChallengeSolutionReveal>,
         id: u64,
+        plaintext_solution: String,
+        salt: [u8; 32],
     ) -> Result<()> {
-        challenge_solution_reveal::handler(ctx, id)
+        challenge_solution_reveal::handler(ctx, id, plaintext_solution, salt)
     }
 
     pub fn submission_solution_reveal(
         ctx: Context<SubmissionSolutionReveal>,
-        nonce: String,
+        salt: [u8; 32],
         plaintext_answer: String,
     ) -> Result<()> {
-        submission_solution_reveal::handler(ctx, nonce, plaintext_answer)
+        submission_solution_reveal::handler(ctx, salt, plaintext_answer)
     }
 }
@@ -1,21 +1,86 @@
 use anchor_lang::prelude::*;
-use crate::{MAX_QUESTION_LENGTH};
+use crate::{BPS_DENOMINATOR, MAX_ALTERNATES, MAX_ALTERNATE_LENGTH, MAX_BYTECODE_LENGTH, MAX_PAYOUT_RANKS, MAX_QUESTION_LENGTH};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ChallengeKind {
+    Expression, // The question is an arithmetic expression, the program computes the solution
+    TextRiddle, // Free text question, the setter commits to the solution and reveals it
+}
+
+// How answers are normalized before they are compared with the solution
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct AnswerNormalization {
+    pub trim: bool, // Ignore leading and trailing whitespace
+    pub lowercase: bool, // Lowercase both sides, which isn't full case folding: `STRASSE` doesn't match `straße`
+    pub nfkc: bool, // Apply Unicode NFKC normalization, e.g. full-width digits become ASCII
+    pub numeric: bool, // Compare numbers by value, so `+18.0` matches `18`
+    #[max_len(MAX_ALTERNATES, MAX_ALTERNATE_LENGTH)]
+    pub alternates: Vec<String>, // Other accepted answers besides the solution
+}
+
+// How the pot left after the setter's cut and the protocol fee is shared among correct answers
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum PayoutStrategy {
+    #[default]
+    EqualSplit, // Every correct answer gets the same share
+    WinnerTakesAll, // The earliest committed correct answer takes the whole prize
+    TopK {
+        #[max_len(MAX_PAYOUT_RANKS)]
+        weights: Vec<u16>, // Relative shares of the earliest committed correct answers, first to last
+    },
+    // One correct answer takes the whole prize, drawn from the setter's seed and the salts of the correct reveals
+    Lottery {
+        seed_hash: [u8; 32], // Setter's seed, see lottery_seed_commitment - opened when the solution is revealed
+    },
+}
 
 #[account]
 #[derive(InitSpace)]
 pub struct ChallengeAccount {
     pub id: u64,
+    pub kind: ChallengeKind,
     #[max_len(MAX_QUESTION_LENGTH)]
     pub question: String,
+    #[max_len(MAX_BYTECODE_LENGTH)]
+    pub bytecode: Vec<u8>, // Compiled question, executed again when the solution is revealed
     #[max_len(MAX_QUESTION_LENGTH)]
     pub solution: String,
+    pub solution_hash: [u8; 32], // Committed by the setter of a text riddle, see solution_commitment
+    pub normalization: AnswerNormalization,
     pub submission_deadline: i64,
     pub answer_reveal_deadline: i64,
     pub claim_deadline: i64,
     pub entry_fee: u64,
+    pub mint: Option<Pubkey>, // Token the entry fee is paid in, held by the vault PDA - lamports when None
     pub setter: Pubkey,
-    pub pot: u64, // Total pot amount - don't update on withdraws so we can calculate each player's share
+    pub pot: u64, // Total pot amount - never decreased, withdrawals are counted in total_paid_out
+    pub setter_cut_bps: u16, // Picked by the setter within the global config's bounds
     pub setter_cut_claimed: bool,
+    pub protocol_fee_bps: u16, // Global config's fee when the challenge was created
+    pub protocol_fee_claimed: bool,
     pub bump: u8,
-    pub correct_submissions: u64,
-}
\ No newline at end of file
+    pub correct_submissions: u64, // Final once the answer reveal deadline has passed, like revealed_count
+    pub submission_count: u64, // Token transfer fees mean the pot isn't a multiple of the entry fee
+    pub payout_strategy: PayoutStrategy,
+    #[max_len(MAX_PAYOUT_RANKS)]
+    pub ranked_submissions: Vec<u64>, // Commit indices of the earliest committed correct answers, ascending
+    pub lottery_seed: [u8; 32], // Setter's lottery seed XORed with the salts of the correct reveals
+    pub total_paid_out: u64, // Setter's cut, protocol fee, prizes and refunds paid out of the pot so far
+    pub claims_count: u64, // Prizes and refunds paid to players
+    pub revealed_count: u64, // Revealed answers, correct or not - claims only open once no more can be revealed
+}
+
+impl ChallengeAccount {
+    pub fn setter_cut(&self) -> u64 {
+        bps_of(self.pot, self.setter_cut_bps)
+    }
+
+    pub fn protocol_fee(&self) -> u64 {
+        bps_of(self.pot, self.protocol_fee_bps)
+    }
+}
+
+// Computed in 128 bits so large pots can't overflow
+fn bps_of(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
@@ -1,8 +1,34 @@
 use anchor_lang::prelude::*;
+use crate::BPS_DENOMINATOR;
+use crate::error::RiddleRushError;
 
 #[account]
 #[derive(InitSpace)]
 pub struct GlobalConfig {
     pub next_challenge_id: u64,
     pub bump: u8,
-} 
\ No newline at end of file
+    pub authority: Pubkey, // Admin of the deployment
+    pub pending_authority: Option<Pubkey>, // Proposed admin, becomes the authority once it accepts
+    pub paused_operations: u8, // Bitmask of PAUSE_* operations currently halted
+    pub treasury: Pubkey, // Receives the protocol fee
+    pub protocol_fee_bps: u16,
+    pub min_setter_cut_bps: u16, // Range of setter cuts a challenge may pick
+    pub max_setter_cut_bps: u16,
+}
+
+impl GlobalConfig {
+    // The setter and the protocol can never take more than the whole pot
+    pub fn validate_fees(&self) -> Result<()> {
+        require!(
+            self.min_setter_cut_bps <= self.max_setter_cut_bps
+                && self.protocol_fee_bps as u64 + self.max_setter_cut_bps as u64 <= BPS_DENOMINATOR,
+            RiddleRushError::InvalidFeeConfig
+        );
+        Ok(())
+    }
+
+    pub fn require_not_paused(&self, operation: u8) -> Result<()> {
+        require!(self.paused_operations & operation == 0, RiddleRushError::ProgramPaused);
+        Ok(())
+    }
+}
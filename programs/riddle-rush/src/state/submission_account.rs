@@ -6,7 +6,11 @@ pub struct SubmissionAccount {
     pub challenge_id: u64,
     pub submitter: Pubkey,
     pub encrypted_answer: [u8; 32],
+    pub commitment_version: u8, // Format of encrypted_answer, see submission_commitment
     pub revealed: bool,
     pub answer_correct: bool,
     pub claimed: bool,
+    pub commit_index: u64, // Position among the challenge's submissions, earlier commits rank first
+    pub correct_index: u64, // Position among the correct reveals, only set when the answer is correct
+    pub amount: u64, // Entry fee the challenge received, net of token transfer fees
 }
\ No newline at end of file
@@ -0,0 +1,461 @@
+// In-process runtime for the integration tests: accounts live in memory, the program runs natively
+// through its entrypoint on the same serialized input the SVM loader builds, CPIs to the system and
+// token programs are emulated and the clock can be warped to any time
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::Once;
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::{AccountInfo, Clock, Pubkey, Rent};
+use anchor_lang::solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use anchor_lang::solana_program::entrypoint::{self, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use anchor_lang::solana_program::system_instruction::{SystemError, SystemInstruction};
+use anchor_lang::solana_program::system_program;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::non_transferable::NonTransferable;
+use anchor_spl::token_2022::spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut};
+use riddle_client::{decode_event, ProgramEvent};
+use riddle_rush::error::RiddleRushError;
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+const NATIVE_LOADER_ID: Pubkey = anchor_lang::pubkey!("NativeLoader1111111111111111111111111111111");
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+thread_local! {
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock::default());
+    static LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+    // Net lamports each account gained or lost inside CPIs, so the rest can be put down to the program
+    static CPI_LAMPORTS: RefCell<HashMap<Pubkey, i128>> = RefCell::new(HashMap::new());
+}
+
+// Syscalls the program makes natively, the state they read is per test thread
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        LOGS.with(|logs| logs.borrow_mut().push(format!("Program log: {}", message)));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        EVENTS.with(|events| events.borrow_mut().push(fields.concat()));
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        CLOCK.with(|clock| unsafe { *(var_addr as *mut Clock) = clock.borrow().clone() });
+        entrypoint::SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        entrypoint::SUCCESS
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke_signed(instruction, account_infos, signers_seeds)
+    }
+}
+
+fn invoke_signed(instruction: &Instruction, account_infos: &[AccountInfo], signers_seeds: &[&[&[u8]]]) -> ProgramResult {
+    // PDAs of the calling program sign for the seeds it passed
+    let pda_signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &riddle_rush::ID))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut accounts = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let mut info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?
+            .clone();
+        if meta.is_signer {
+            if !info.is_signer && !pda_signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            info.is_signer = true;
+        }
+        accounts.push(info);
+    }
+
+    let before: Vec<(Pubkey, Pubkey, u64)> = accounts.iter().map(|info| (*info.key, *info.owner, info.lamports())).collect();
+    if instruction.program_id == system_program::ID {
+        process_system_instruction(&instruction.data, &accounts)
+    } else if instruction.program_id == spl_token::ID {
+        spl_token::processor::Processor::process(&instruction.program_id, &accounts, &instruction.data)
+    } else if instruction.program_id == spl_token_2022::ID {
+        spl_token_2022::processor::Processor::process(&instruction.program_id, &accounts, &instruction.data)
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    }?;
+
+    // As in the runtime, only the program that owns an account may take lamports out of it
+    let mut seen = Vec::with_capacity(before.len());
+    for ((key, owner, lamports), info) in before.into_iter().zip(&accounts) {
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+        let delta = info.lamports() as i128 - lamports as i128;
+        if delta < 0 && owner != instruction.program_id {
+            panic!("{} took lamports from {} owned by {}", instruction.program_id, key, owner);
+        }
+        CPI_LAMPORTS.with(|cpi| *cpi.borrow_mut().entry(key).or_default() += delta);
+    }
+    Ok(())
+}
+
+// The subset of the system program the program calls into
+fn process_system_instruction(data: &[u8], accounts: &[AccountInfo]) -> ProgramResult {
+    let instruction: SystemInstruction = bincode::deserialize(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let signed = |index: usize| match accounts.get(index) {
+        Some(info) if info.is_signer => Ok(info),
+        Some(_) => Err(ProgramError::MissingRequiredSignature),
+        None => Err(ProgramError::NotEnoughAccountKeys),
+    };
+    match instruction {
+        SystemInstruction::CreateAccount { lamports, space, owner } => {
+            let (from, to) = (signed(0)?, signed(1)?);
+            if to.lamports() > 0 || !to.data_is_empty() {
+                return Err(ProgramError::Custom(SystemError::AccountAlreadyInUse as u32));
+            }
+            move_lamports(from, to, lamports)?;
+            to.realloc(space as usize, true)?;
+            to.assign(&owner);
+            Ok(())
+        },
+        SystemInstruction::Transfer { lamports } => {
+            let from = signed(0)?;
+            let to = accounts.get(1).ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if !from.data_is_empty() {
+                return Err(ProgramError::InvalidArgument);
+            }
+            move_lamports(from, to, lamports)
+        },
+        SystemInstruction::Allocate { space } => signed(0)?.realloc(space as usize, true),
+        SystemInstruction::Assign { owner } => {
+            signed(0)?.assign(&owner);
+            Ok(())
+        },
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if from.lamports() < lamports {
+        return Err(ProgramError::Custom(SystemError::ResultWithNegativeLamports as u32));
+    }
+    **from.try_borrow_mut_lamports()? -= lamports;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+pub struct Svm {
+    accounts: HashMap<Pubkey, Account>,
+    pub logs: Vec<String>,
+}
+
+impl Svm {
+    // A runtime with the program deployed by `upgrade_authority`
+    pub fn new(upgrade_authority: &Pubkey) -> Self {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(Stubs));
+        });
+
+        let mut svm = Svm {
+            accounts: HashMap::new(),
+            logs: Vec::new(),
+        };
+        for program in [system_program::ID, spl_token::ID, spl_token_2022::ID] {
+            svm.set_account(program, Account { lamports: rent_exempt(0), data: Vec::new(), owner: NATIVE_LOADER_ID, executable: true });
+        }
+        let program_data = riddle_client::program_data_address();
+        let program = bincode::serialize(&UpgradeableLoaderState::Program { programdata_address: program_data }).unwrap();
+        svm.set_account(
+            riddle_rush::ID,
+            Account {
+                lamports: rent_exempt(program.len()),
+                data: program,
+                owner: bpf_loader_upgradeable::ID,
+                executable: true,
+            },
+        );
+        let data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(*upgrade_authority),
+        })
+        .unwrap();
+        svm.set_account(
+            program_data,
+            Account {
+                lamports: rent_exempt(data.len()),
+                data,
+                owner: bpf_loader_upgradeable::ID,
+                executable: false,
+            },
+        );
+        svm.warp_to(0);
+        svm
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.accounts.insert(address, account);
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<&Account> {
+        self.accounts.get(address)
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.account(address).map_or(0, |account| account.lamports)
+    }
+
+    pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        self.accounts.entry(*address).or_insert_with(|| Account { owner: system_program::ID, ..Account::default() }).lamports += lamports;
+    }
+
+    pub fn now(&self) -> i64 {
+        CLOCK.with(|clock| clock.borrow().unix_timestamp)
+    }
+
+    // Moves the clock to `unix_timestamp`, advancing the slot so each warp looks like a new block
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        CLOCK.with(|clock| {
+            let mut clock = clock.borrow_mut();
+            clock.slot += 1;
+            clock.unix_timestamp = unix_timestamp;
+        });
+    }
+
+    // Runs the instruction as a transaction signed by `signers`. Account changes are only kept when it
+    // succeeds, and the events it emitted are returned. Like the runtime it fails the test when lamports
+    // are created or destroyed, taken from an account by a program that doesn't own it, or leave an
+    // account below the rent-exempt minimum
+    pub fn process(&mut self, instruction: &Instruction, signers: &[&Pubkey]) -> Result<Vec<ProgramEvent>, ProgramError> {
+        // Accounts that appear twice share one serialized copy, with the union of their privileges
+        let mut keys: Vec<(Pubkey, bool, bool)> = Vec::new();
+        let mut indices = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            if meta.is_signer && !signers.contains(&&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            match keys.iter().position(|(key, _, _)| *key == meta.pubkey) {
+                Some(index) => {
+                    keys[index].1 |= meta.is_signer;
+                    keys[index].2 |= meta.is_writable;
+                    indices.push(index);
+                },
+                None => {
+                    keys.push((meta.pubkey, meta.is_signer, meta.is_writable));
+                    indices.push(keys.len() - 1);
+                },
+            }
+        }
+        let mut input = serialize(&self.accounts, &keys, &indices, &instruction.data, &instruction.program_id);
+        let lamports_before: u64 = keys.iter().map(|(key, _, _)| self.lamports(key)).sum();
+
+        LOGS.with(|logs| logs.borrow_mut().clear());
+        EVENTS.with(|events| events.borrow_mut().clear());
+        CPI_LAMPORTS.with(|cpi| cpi.borrow_mut().clear());
+        let (program_id, infos, data) = unsafe { entrypoint::deserialize(input.as_mut_ptr() as *mut u8) };
+        let result = riddle_rush::entry(program_id, &infos, data);
+        self.logs = LOGS.with(|logs| logs.borrow().clone());
+        result?;
+
+        let mut updated = Vec::with_capacity(keys.len());
+        for (key, _, is_writable) in &keys {
+            let info = infos.iter().find(|info| info.key == key).unwrap();
+            let account = Account {
+                lamports: info.lamports(),
+                data: info.data.borrow().to_vec(),
+                owner: *info.owner,
+                executable: info.executable,
+            };
+            let previous = self.accounts.get(key).cloned().unwrap_or_default();
+            if !is_writable && account != previous && !(previous == Account::default() && account.lamports == 0) {
+                panic!("read-only account {} was modified", key);
+            }
+            // Whatever moved outside of CPIs was moved by the program itself, which may only debit its own accounts
+            let cpi_delta = CPI_LAMPORTS.with(|cpi| cpi.borrow().get(key).copied().unwrap_or_default());
+            let direct_delta = account.lamports as i128 - previous.lamports as i128 - cpi_delta;
+            if direct_delta < 0 && previous.owner != riddle_rush::ID && account.owner != riddle_rush::ID {
+                panic!("the program took lamports from {} owned by {}", key, previous.owner);
+            }
+            if account.lamports > 0 && account.lamports < rent_exempt(account.data.len()) {
+                panic!("{} was left with {} lamports, below the rent-exempt minimum", key, account.lamports);
+            }
+            updated.push((*key, account));
+        }
+        let lamports_after: u64 = updated.iter().map(|(_, account)| account.lamports).sum();
+        assert_eq!(lamports_before, lamports_after, "the instruction created or destroyed lamports");
+        for (key, account) in updated {
+            // Accounts left without lamports are garbage collected at the end of the transaction
+            if account.lamports == 0 {
+                self.accounts.remove(&key);
+            } else {
+                self.accounts.insert(key, account);
+            }
+        }
+
+        Ok(EVENTS.with(|events| events.borrow().iter().filter_map(|data| decode_event(data)).collect()))
+    }
+}
+
+// Lays out the input exactly as the loader does: each account's header, data and realloc padding
+// followed by the instruction data and program id
+fn serialize(
+    accounts: &HashMap<Pubkey, Account>,
+    keys: &[(Pubkey, bool, bool)],
+    indices: &[usize],
+    data: &[u8],
+    program_id: &Pubkey,
+) -> Vec<u128> {
+    let mut bytes: Vec<u8> = Vec::new();
+    bytes.extend_from_slice(&(indices.len() as u64).to_le_bytes());
+    // Duplicates point at the position of the key's first occurrence in the instruction
+    let mut first_position = vec![None; keys.len()];
+    for (position, &index) in indices.iter().enumerate() {
+        if let Some(first) = first_position[index] {
+            bytes.push(first as u8);
+            bytes.extend_from_slice(&[0u8; 7]);
+            continue;
+        }
+        first_position[index] = Some(position);
+        let (key, is_signer, is_writable) = keys[index];
+        let default = Account { owner: system_program::ID, ..Account::default() };
+        let account = accounts.get(&key).unwrap_or(&default);
+        bytes.extend_from_slice(&[NON_DUP_MARKER, is_signer as u8, is_writable as u8, account.executable as u8]);
+        bytes.extend_from_slice(&[0u8; 4]);
+        bytes.extend_from_slice(key.as_ref());
+        bytes.extend_from_slice(account.owner.as_ref());
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len().next_multiple_of(size_of::<u128>() / 2), 0);
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes()); // rent epoch
+    }
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes.extend_from_slice(program_id.as_ref());
+
+    // Backed by u128s so the u64 fields the entrypoint reads in place are aligned
+    let mut input = vec![0u128; bytes.len().div_ceil(size_of::<u128>())];
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), input.as_mut_ptr() as *mut u8, bytes.len()) };
+    input
+}
+
+pub fn rent_exempt(data_len: usize) -> u64 {
+    Rent::default().minimum_balance(data_len)
+}
+
+// A mint of the classic token program, without a freeze authority
+pub fn mint_account(authority: &Pubkey, decimals: u8) -> Account {
+    let mut data = vec![0u8; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: Some(*authority).into(),
+        supply: u64::MAX / 2,
+        decimals,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    }
+    .pack_into_slice(&mut data);
+    Account { lamports: rent_exempt(data.len()), data, owner: spl_token::ID, executable: false }
+}
+
+// Token-2022 mint with a single extension in its default configuration
+pub fn token_2022_mint_account(authority: &Pubkey, decimals: u8, extension: ExtensionType) -> Account {
+    let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[extension]).unwrap();
+    let mut data = vec![0u8; len];
+    let mut state = StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data).unwrap();
+    match extension {
+        ExtensionType::TransferFeeConfig => {
+            state.init_extension::<TransferFeeConfig>(true).unwrap();
+        },
+        ExtensionType::PermanentDelegate => {
+            state.init_extension::<PermanentDelegate>(true).unwrap().delegate = Some(*authority).try_into().unwrap();
+        },
+        ExtensionType::NonTransferable => {
+            state.init_extension::<NonTransferable>(true).unwrap();
+        },
+        extension => panic!("unsupported extension {:?}", extension),
+    }
+    state.base = spl_token_2022::state::Mint {
+        mint_authority: Some(*authority).into(),
+        supply: u64::MAX / 2,
+        decimals,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    Account { lamports: rent_exempt(len), data, owner: spl_token_2022::ID, executable: false }
+}
+
+// Token-2022 account without extensions, which is enough to send tokens from
+pub fn token_2022_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0u8; spl_token_2022::state::Account::LEN];
+    spl_token_2022::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: spl_token_2022::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    Account { lamports: rent_exempt(data.len()), data, owner: spl_token_2022::ID, executable: false }
+}
+
+pub fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0u8; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    Account { lamports: rent_exempt(data.len()), data, owner: spl_token::ID, executable: false }
+}
+
+pub fn token_balance(svm: &Svm, address: &Pubkey) -> u64 {
+    let account = svm.account(address).expect("missing token account");
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+pub fn program_error(error: RiddleRushError) -> ProgramError {
+    ProgramError::Custom(ERROR_CODE_OFFSET + error as u32)
+}
+
+#[track_caller]
+pub fn assert_error(result: Result<Vec<ProgramEvent>, ProgramError>, expected: RiddleRushError) {
+    match result {
+        Ok(_) => panic!("expected {:?}, the instruction succeeded", expected),
+        Err(e) => assert_eq!(e, program_error(expected), "expected {:?}", expected),
+    }
+}
@@ -0,0 +1,751 @@
+// Runs challenges through their whole lifecycle, warping the clock through the submission, reveal and
+// claim windows, and checks every handler including the ways each one can fail
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Space;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use common::*;
+use riddle_client::*;
+use riddle_rush::error::RiddleRushError;
+use riddle_rush::{PAUSE_ALL, PAUSE_CLAIM, PAUSE_SUBMIT};
+
+const START: i64 = 1_000;
+const SUBMISSION_DEADLINE: i64 = 2_000;
+const ANSWER_REVEAL_DEADLINE: i64 = 3_000;
+const CLAIM_DEADLINE: i64 = 4_000;
+const ENTRY_FEE: u64 = LAMPORTS_PER_SOL;
+const PROTOCOL_FEE_BPS: u16 = 100;
+const SETTER_CUT_BPS: u16 = 1_000;
+
+struct Test {
+    svm: Svm,
+    authority: Pubkey,
+    treasury: Pubkey,
+}
+
+impl Test {
+    // A deployment initialized by its upgrade authority, with the clock at `START`
+    fn new() -> Self {
+        let authority = Pubkey::new_unique();
+        let treasury = Pubkey::new_unique();
+        let mut svm = Svm::new(&authority);
+        svm.airdrop(&authority, 10 * LAMPORTS_PER_SOL);
+        svm.airdrop(&treasury, LAMPORTS_PER_SOL);
+        svm.warp_to(START);
+        svm.process(&initialize(&authority, &fees(treasury, PROTOCOL_FEE_BPS)), &[&authority]).unwrap();
+        Test { svm, authority, treasury }
+    }
+
+    fn user(&mut self) -> Pubkey {
+        let user = Pubkey::new_unique();
+        self.svm.airdrop(&user, 10 * LAMPORTS_PER_SOL);
+        user
+    }
+
+    fn config(&self) -> GlobalConfig {
+        decode_global_config(&self.svm.account(&global_config_address()).unwrap().data).unwrap()
+    }
+
+    fn challenge(&self, id: u64) -> ChallengeAccount {
+        decode_challenge(&self.svm.account(&challenge_address(id)).unwrap().data).unwrap()
+    }
+
+    fn submission(&self, id: u64, submitter: &Pubkey) -> SubmissionAccount {
+        let address = submission_address(&challenge_address(id), submitter);
+        decode_submission(&self.svm.account(&address).unwrap().data).unwrap()
+    }
+
+    fn create(&mut self, setter: &Pubkey, args: CreateChallengeArgs, tokens: Option<TokenAccounts>) -> u64 {
+        let id = self.config().next_challenge_id;
+        let events = self.svm.process(&create_challenge(setter, id, args, tokens), &[setter]).unwrap();
+        assert!(matches!(events[..], [ProgramEvent::ChallengeCreated(ref e)] if e.challenge_id == id));
+        id
+    }
+
+    fn create_expression(&mut self, setter: &Pubkey, question: &str) -> u64 {
+        self.create(setter, expression_args(question), None)
+    }
+
+    // Commits to `answer` with a nonce derived from the submitter, so the reveal can recompute it
+    fn submit(&mut self, id: u64, submitter: &Pubkey, answer: &str, tokens: Option<TokenAccounts>) -> Result<Vec<ProgramEvent>, ProgramError> {
+        let commitment = answer_commitment(&challenge_address(id), submitter, answer, &nonce(submitter));
+        self.svm.process(&create_submission(submitter, id, commitment, tokens), &[submitter])
+    }
+
+    fn reveal(&mut self, id: u64, submitter: &Pubkey, answer: &str) -> Result<Vec<ProgramEvent>, ProgramError> {
+        let instruction = submission_solution_reveal(submitter, id, nonce(submitter), answer.to_string());
+        self.svm.process(&instruction, &[submitter])
+    }
+
+    fn reveal_solution(&mut self, user: &Pubkey, id: u64, solution: &str, salt: [u8; 32]) -> Result<Vec<ProgramEvent>, ProgramError> {
+        let instruction = challenge_solution_reveal(user, id, solution.to_string(), salt);
+        self.svm.process(&instruction, &[user])
+    }
+
+    fn claim(&mut self, id: u64, submitter: &Pubkey, tokens: Option<TokenAccounts>) -> Result<Vec<ProgramEvent>, ProgramError> {
+        self.svm.process(&submitter_claim(submitter, id, tokens), &[submitter])
+    }
+}
+
+fn fees(treasury: Pubkey, protocol_fee_bps: u16) -> FeeConfig {
+    FeeConfig {
+        treasury,
+        protocol_fee_bps,
+        min_setter_cut_bps: 0,
+        max_setter_cut_bps: 2_000,
+    }
+}
+
+fn expression_args(question: &str) -> CreateChallengeArgs {
+    CreateChallengeArgs {
+        question: question.to_string(),
+        submission_deadline: SUBMISSION_DEADLINE,
+        answer_reveal_deadline: ANSWER_REVEAL_DEADLINE,
+        claim_deadline: CLAIM_DEADLINE,
+        entry_fee: ENTRY_FEE,
+        kind: ChallengeKind::Expression,
+        solution_hash: [0u8; 32],
+        normalization: AnswerNormalization::default(),
+        setter_cut_bps: SETTER_CUT_BPS,
+        payout_strategy: PayoutStrategy::EqualSplit,
+    }
+}
+
+fn nonce(submitter: &Pubkey) -> [u8; 32] {
+    derive_nonce(submitter.as_ref())
+}
+
+fn submission_rent() -> u64 {
+    rent_exempt(8 + SubmissionAccount::INIT_SPACE)
+}
+
+fn account_not_initialized() -> ProgramError {
+    ProgramError::Custom(ErrorCode::AccountNotInitialized as u32)
+}
+
+fn constraint_error() -> ProgramError {
+    ProgramError::Custom(ErrorCode::ConstraintRaw as u32)
+}
+
+#[test]
+fn governance() {
+    let mut test = Test::new();
+    let config = test.config();
+    assert_eq!(config.authority, test.authority);
+    assert_eq!(config.treasury, test.treasury);
+    assert_eq!(config.next_challenge_id, 0);
+
+    // Only the upgrade authority may initialize a deployment
+    let mut other = Svm::new(&test.authority);
+    let stranger = Pubkey::new_unique();
+    other.airdrop(&stranger, LAMPORTS_PER_SOL);
+    assert_error(other.process(&initialize(&stranger, &fees(stranger, 0)), &[&stranger]), RiddleRushError::NotUpgradeAuthority);
+
+    // Fees that would take more than the whole pot are rejected
+    let authority = test.authority;
+    let treasury = test.treasury;
+    assert_error(test.svm.process(&update_config(&authority, &fees(treasury, 9_000)), &[&authority]), RiddleRushError::InvalidFeeConfig);
+    test.svm.process(&update_config(&authority, &fees(treasury, 200)), &[&authority]).unwrap();
+    assert_eq!(test.config().protocol_fee_bps, 200);
+    let result = test.svm.process(&update_config(&stranger, &fees(stranger, 0)), &[&stranger]);
+    assert_eq!(result.err(), Some(ProgramError::Custom(ErrorCode::ConstraintHasOne as u32)));
+
+    // The authority moves in two steps, only the proposed key can accept
+    let successor = test.user();
+    test.svm.process(&propose_authority(&authority, Some(successor)), &[&authority]).unwrap();
+    assert_error(test.svm.process(&accept_authority(&stranger), &[&stranger]), RiddleRushError::NotPendingAuthority);
+    test.svm.process(&accept_authority(&successor), &[&successor]).unwrap();
+    let config = test.config();
+    assert_eq!(config.authority, successor);
+    assert_eq!(config.pending_authority, None);
+
+    // Paused operations fail until they are resumed
+    assert_error(test.svm.process(&set_paused(&successor, 1 << 7), &[&successor]), RiddleRushError::InvalidPauseMask);
+    test.svm.process(&set_paused(&successor, PAUSE_SUBMIT), &[&successor]).unwrap();
+    let setter = test.user();
+    let id = test.create_expression(&setter, "6 * 7");
+    let player = test.user();
+    assert_error(test.submit(id, &player, "42", None), RiddleRushError::ProgramPaused);
+    test.svm.process(&set_paused(&successor, PAUSE_ALL), &[&successor]).unwrap();
+    let args = expression_args("1 + 1");
+    assert_error(test.svm.process(&create_challenge(&setter, 1, args, None), &[&setter]), RiddleRushError::ProgramPaused);
+    test.svm.process(&set_paused(&successor, 0), &[&successor]).unwrap();
+    test.submit(id, &player, "42", None).unwrap();
+}
+
+#[test]
+fn expression_challenge_lifecycle() {
+    let mut test = Test::new();
+    let setter = test.user();
+    let [alice, bob, carol, dave, erin] = [test.user(), test.user(), test.user(), test.user(), test.user()];
+
+    // Deadlines must be ordered and still ahead
+    let mut args = expression_args("6 * 7");
+    args.answer_reveal_deadline = SUBMISSION_DEADLINE;
+    let result = test.svm.process(&create_challenge(&setter, 0, args, None), &[&setter]);
+    assert_error(result, RiddleRushError::AnswerRevealDeadlinBeforeSubmissionDeadline);
+    let mut args = expression_args("6 * 7");
+    args.submission_deadline = START;
+    assert_error(test.svm.process(&create_challenge(&setter, 0, args, None), &[&setter]), RiddleRushError::SubmissionDeadlinePassed);
+    let mut args = expression_args("6 * 7");
+    args.setter_cut_bps = 5_000;
+    assert_error(test.svm.process(&create_challenge(&setter, 0, args, None), &[&setter]), RiddleRushError::SetterCutOutOfRange);
+
+    let setter_balance = test.svm.lamports(&setter);
+    let id = test.create_expression(&setter, "6 * 7");
+    let challenge = test.challenge(id);
+    assert_eq!(challenge.pot, ENTRY_FEE);
+    let challenge_rent = rent_exempt(test.svm.account(&challenge_address(id)).unwrap().data.len());
+    assert_eq!(test.svm.lamports(&setter), setter_balance - ENTRY_FEE - challenge_rent);
+
+    // Submission window
+    for (player, answer) in [(&alice, "42"), (&bob, "42"), (&carol, "41"), (&erin, "42")] {
+        let events = test.submit(id, player, answer, None).unwrap();
+        assert!(matches!(events[..], [ProgramEvent::SubmissionCreated(ref e)] if e.submitter == *player));
+    }
+    let commitment = answer_commitment(&challenge_address(id), &dave, "42", &nonce(&dave));
+    let mut instruction = create_submission(&dave, id, commitment, None);
+    *instruction.data.last_mut().unwrap() = COMMITMENT_VERSION + 1;
+    assert_error(test.svm.process(&instruction, &[&dave]), RiddleRushError::UnsupportedCommitmentVersion);
+    assert_error(test.reveal_solution(&setter, id, "", [0; 32]), RiddleRushError::SolutionRevealDeadlineNotMet);
+    assert_error(test.reveal(id, &alice, "42"), RiddleRushError::SolutionNotRevealed);
+    let challenge = test.challenge(id);
+    assert_eq!(challenge.pot, 5 * ENTRY_FEE);
+    assert_eq!(challenge.submission_count, 4);
+
+    // Reveal window
+    test.svm.warp_to(SUBMISSION_DEADLINE);
+    assert_error(test.submit(id, &dave, "42", None), RiddleRushError::SubmissionDeadlinePassed);
+    assert_error(test.reveal_solution(&dave, id, "", [0; 32]), RiddleRushError::SolutionRevealDeadlineNotMet);
+    test.svm.warp_to(SUBMISSION_DEADLINE + 1);
+    let events = test.reveal_solution(&dave, id, "", [0; 32]).unwrap();
+    assert!(matches!(events[..], [ProgramEvent::SolutionRevealed(ref e)] if e.solution == "42"));
+    // Revealing again is a no-op
+    assert!(test.reveal_solution(&dave, id, "", [0; 32]).unwrap().is_empty());
+
+    assert_error(test.reveal(id, &alice, "43"), RiddleRushError::AnswerMismatch);
+    let salt = [7u8; 32];
+    let result = test.svm.process(&submission_solution_reveal(&alice, id, salt, "42".to_string()), &[&alice]);
+    assert_error(result, RiddleRushError::AnswerMismatch);
+    let events = test.reveal(id, &alice, "42").unwrap();
+    assert!(matches!(events[..], [ProgramEvent::SubmissionRevealed(ref e)] if e.answer_correct && e.correct_submissions == 1));
+    assert_error(test.reveal(id, &alice, "42"), RiddleRushError::SubmissionAlreadyRevealed);
+    test.reveal(id, &bob, "42").unwrap();
+    // Answers can be revealed up to and including the deadline
+    test.svm.warp_to(ANSWER_REVEAL_DEADLINE);
+    let events = test.reveal(id, &carol, "41").unwrap();
+    assert!(matches!(events[..], [ProgramEvent::SubmissionRevealed(ref e)] if !e.answer_correct));
+    assert!(test.submission(id, &alice).answer_correct);
+    assert!(!test.submission(id, &carol).answer_correct);
+    assert_eq!(test.challenge(id).correct_submissions, 2);
+
+    // Nothing can be claimed until the reveal window is over
+    assert_error(test.claim(id, &alice, None), RiddleRushError::WithdrawTooEarly);
+    assert_error(test.svm.process(&setter_claim(&setter, id, None), &[&setter]), RiddleRushError::WithdrawTooEarly);
+    let treasury = test.treasury;
+    let result = test.svm.process(&treasury_claim(&dave, &treasury, id, None), &[&dave]);
+    assert_error(result, RiddleRushError::WithdrawTooEarly);
+
+    // Claim window, Erin's reveal is too late to count
+    test.svm.warp_to(ANSWER_REVEAL_DEADLINE + 1);
+    assert_error(test.reveal(id, &erin, "42"), RiddleRushError::AnswerRevealDeadlinePassed);
+    assert_eq!(test.challenge(id).correct_submissions, 2);
+    assert_error(test.claim(id, &erin, None), RiddleRushError::AnswerMismatch);
+    let pot = 5 * ENTRY_FEE;
+    let setter_cut = pot * SETTER_CUT_BPS as u64 / 10_000;
+    let protocol_fee = pot * PROTOCOL_FEE_BPS as u64 / 10_000;
+    let share = (pot - setter_cut - protocol_fee) / 2;
+
+    assert_error(test.claim(id, &carol, None), RiddleRushError::AnswerMismatch);
+    let balance = test.svm.lamports(&alice);
+    let events = test.claim(id, &alice, None).unwrap();
+    assert!(matches!(events[..], [ProgramEvent::SubmitterClaimed(ref e)] if e.amount == share));
+    // The claim closes the submission, so it can't be claimed twice
+    assert_eq!(test.svm.lamports(&alice), balance + share + submission_rent());
+    assert_eq!(test.claim(id, &alice, None).err(), Some(account_not_initialized()));
+
+    let balance = test.svm.lamports(&setter);
+    test.svm.process(&setter_claim(&setter, id, None), &[&setter]).unwrap();
+    assert_eq!(test.svm.lamports(&setter), balance + setter_cut);
+    assert_eq!(test.svm.process(&setter_claim(&setter, id, None), &[&setter]).err(), Some(constraint_error()));
+    assert_eq!(test.svm.process(&setter_claim(&dave, id, None), &[&dave]).err(), Some(ProgramError::Custom(ErrorCode::ConstraintHasOne as u32)));
+
+    let balance = test.svm.lamports(&treasury);
+    let events = test.svm.process(&treasury_claim(&dave, &treasury, id, None), &[&dave]).unwrap();
+    assert!(matches!(events[..], [ProgramEvent::TreasuryClaimed(ref e)] if e.amount == protocol_fee));
+    assert_eq!(test.svm.lamports(&treasury), balance + protocol_fee);
+
+    // Bob misses the claim window, his share goes back to the setter when the challenge is closed
+    test.svm.warp_to(CLAIM_DEADLINE);
+    assert_error(test.claim(id, &bob, None), RiddleRushError::WithdrawTooLate);
+    assert_error(test.svm.process(&setter_close_challenge(&setter, id, None), &[&setter]), RiddleRushError::WithdrawTooEarly);
+    test.svm.warp_to(CLAIM_DEADLINE + 1);
+    let remaining = test.svm.lamports(&challenge_address(id));
+    assert_eq!(remaining, challenge_rent + pot - setter_cut - protocol_fee - share);
+    let balance = test.svm.lamports(&setter);
+    let events = test.svm.process(&setter_close_challenge(&setter, id, None), &[&setter]).unwrap();
+    assert!(matches!(events[..], [ProgramEvent::ChallengeClosed(ref e)] if e.lamports == remaining));
+    assert_eq!(test.svm.lamports(&setter), balance + remaining);
+    assert!(test.svm.account(&challenge_address(id)).is_none());
+}
+
+#[test]
+fn text_riddle_lifecycle() {
+    let mut test = Test::new();
+    let setter = test.user();
+    let [alice, bob] = [test.user(), test.user()];
+    let salt = [0x5a; 32];
+
+    let mut args = expression_args("What has cities, but no houses?");
+    args.kind = ChallengeKind::TextRiddle;
+    assert_error(test.svm.process(&create_challenge(&setter, 0, args.clone(), None), &[&setter]), RiddleRushError::MissingSolutionHash);
+    args.solution_hash = solution_commitment(&challenge_address(0), "A map", &salt);
+    // Alternates would be readable on the challenge account during the submission window
+    args.normalization = AnswerNormalization {
+        trim: true,
+        lowercase: true,
+        alternates: vec!["An atlas".to_string()],
+        ..AnswerNormalization::default()
+    };
+    assert_error(test.svm.process(&create_challenge(&setter, 0, args.clone(), None), &[&setter]), RiddleRushError::AlternatesOnTextRiddle);
+    args.normalization.alternates.clear();
+    let id = test.create(&setter, args, None);
+    assert!(test.challenge(id).bytecode.is_empty());
+    // The hash is bound to the challenge, so it can't be copied into another one
+    assert_ne!(solution_commitment(&challenge_address(id + 1), "A map", &salt), test.challenge(id).solution_hash);
+
+    test.submit(id, &alice, "  a MAP ", None).unwrap();
+    test.submit(id, &bob, "a map", None).unwrap();
+
+    test.svm.warp_to(SUBMISSION_DEADLINE + 1);
+    assert_error(test.reveal_solution(&setter, id, "", salt), RiddleRushError::EmptySolution);
+    assert_error(test.reveal_solution(&setter, id, "A globe", salt), RiddleRushError::SolutionHashMismatch);
+    assert_error(test.reveal_solution(&setter, id, "A map", [0x5b; 32]), RiddleRushError::SolutionHashMismatch);
+    test.reveal_solution(&setter, id, "A map", salt).unwrap();
+    assert_eq!(test.challenge(id).solution, "A map");
+
+    test.reveal(id, &alice, "  a MAP ").unwrap();
+    test.reveal(id, &bob, "a map").unwrap();
+    assert_eq!(test.challenge(id).correct_submissions, 2);
+
+    test.svm.warp_to(ANSWER_REVEAL_DEADLINE + 1);
+    let pot = 3 * ENTRY_FEE;
+    let share = (pot - pot * SETTER_CUT_BPS as u64 / 10_000 - pot * PROTOCOL_FEE_BPS as u64 / 10_000) / 2;
+    for player in [alice, bob] {
+        let events = test.claim(id, &player, None).unwrap();
+        assert!(matches!(events[..], [ProgramEvent::SubmitterClaimed(ref e)] if e.amount == share));
+    }
+}
+
+#[test]
+fn nobody_correct_refunds_every_revealed_submitter() {
+    let mut test = Test::new();
+    let setter = test.user();
+    let players = [test.user(), test.user(), test.user(), test.user()];
+    let id = test.create_expression(&setter, "2 ^ 10");
+    for player in &players {
+        test.submit(id, player, "1000", None).unwrap();
+    }
+
+    test.svm.warp_to(SUBMISSION_DEADLINE + 1);
+    test.reveal_solution(&setter, id, "", [0; 32]).unwrap();
+    for player in &players[..3] {
+        test.reveal(id, player, "1000").unwrap();
+    }
+    let challenge = test.challenge(id);
+    assert_eq!((challenge.correct_submissions, challenge.revealed_count), (0, 3));
+
+    // Everyone who revealed gets an equal share of what is left after the setter's cut and the fee,
+    // and the last claim also takes the rounding remainder
+    test.svm.warp_to(ANSWER_REVEAL_DEADLINE + 1);
+    let pot = 5 * ENTRY_FEE;
+    let prize = pot - pot * SETTER_CUT_BPS as u64 / 10_000 - pot * PROTOCOL_FEE_BPS as u64 / 10_000;
+    let refund = prize / 3;
+    assert_ne!(prize % 3, 0);
+    for (index, player) in players[..3].iter().enumerate() {
+        let balance = test.svm.lamports(player);
+        test.claim(id, player, None).unwrap();
+        let refund = if index == 2 { prize - 2 * refund } else { refund };
+        assert_eq!(test.svm.lamports(player), balance + refund + submission_rent());
+    }
+    let challenge = test.challenge(id);
+    assert_eq!((challenge.claims_count, challenge.total_paid_out), (3, prize));
+
+    // The player who never revealed has nothing to claim and can only recover their rent
+    let absent = players[3];
+    assert_error(test.claim(id, &absent, None), RiddleRushError::SubmissionNotRevealed);
+    test.svm.process(&close_submission(&absent, id), &[&absent]).unwrap();
+
+    // Claims can be halted by the authority like any other operation
+    let authority = test.authority;
+    test.svm.process(&set_paused(&authority, PAUSE_CLAIM), &[&authority]).unwrap();
+    assert_error(test.svm.process(&setter_claim(&setter, id, None), &[&setter]), RiddleRushError::ProgramPaused);
+}
+
+#[test]
+fn rounding_remainder_goes_to_the_last_claim() {
+    let mut test = Test::new();
+    let setter = test.user();
+    let winners = [test.user(), test.user(), test.user()];
+    let id = test.create_expression(&setter, "3 + 4");
+    for winner in &winners {
+        test.submit(id, winner, "7", None).unwrap();
+    }
+    test.svm.warp_to(SUBMISSION_DEADLINE + 1);
+    test.reveal_solution(&setter, id, "", [0; 32]).unwrap();
+    for winner in &winners {
+        test.reveal(id, winner, "7").unwrap();
+    }
+
+    // The setter and the treasury are paid in the middle of the winners' claims
+    test.svm.warp_to(ANSWER_REVEAL_DEADLINE + 1);
+    let pot = 4 * ENTRY_FEE;
+    let challenge = test.challenge(id);
+    let prize = challenge.prize();
+    let share = prize / 3;
+    assert_eq!(prize % 3, 2);
+    test.claim(id, &winners[0], None).unwrap();
+    test.svm.process(&setter_claim(&setter, id, None), &[&setter]).unwrap();
+    let treasury = test.treasury;
+    test.svm.process(&treasury_claim(&setter, &treasury, id, None), &[&setter]).unwrap();
+    test.claim(id, &winners[1], None).unwrap();
+    let balance = test.svm.lamports(&winners[2]);
+    let events = test.claim(id, &winners[2], None).unwrap();
+    assert!(events.iter().any(|event| matches!(event, ProgramEvent::SubmitterClaimed(e) if e.amount == share + 2)));
+    assert_eq!(test.svm.lamports(&winners[2]), balance + share + 2 + submission_rent());
+
+    // The whole pot was paid out and only the challenge's rent is left
+    let challenge = test.challenge(id);
+    assert_eq!((challenge.claims_count, challenge.total_paid_out), (3, pot));
+    let address = challenge_address(id);
+    let data_len = test.svm.account(&address).unwrap().data.len();
+    assert_eq!(test.svm.lamports(&address), rent_exempt(data_len));
+}
+
+#[test]
+fn late_reveal_cannot_change_the_shares() {
+    let mut test = Test::new();
+    let setter = test.user();
+    let [alice, bob, carol] = [test.user(), test.user(), test.user()];
+    let id = test.create_expression(&setter, "3 * 3");
+    for player in [alice, bob, carol] {
+        test.submit(id, &player, "9", None).unwrap();
+    }
+    test.svm.warp_to(SUBMISSION_DEADLINE + 1);
+    test.reveal_solution(&setter, id, "", [0; 32]).unwrap();
+    test.reveal(id, &alice, "9").unwrap();
+    test.reveal(id, &bob, "9").unwrap();
+
+    // Carol's reveal would make her a third winner after Alice was paid half the prize
+    test.svm.warp_to(ANSWER_REVEAL_DEADLINE + 1);
+    let prize = test.challenge(id).prize();
+    let share = prize / 2;
+    test.claim(id, &alice, None).unwrap();
+    assert_error(test.reveal(id, &carol, "9"), RiddleRushError::AnswerRevealDeadlinePassed);
+    let challenge = test.challenge(id);
+    assert_eq!((challenge.correct_submissions, challenge.revealed_count, challenge.claims_count), (2, 2, 1));
+    assert_eq!(challenge.total_paid_out, share);
+
+    // Bob's is still the last claim and takes the remainder, Carol has nothing to claim
+    let events = test.claim(id, &bob, None).unwrap();
+    assert!(events.iter().any(|event| matches!(event, ProgramEvent::SubmitterClaimed(e) if e.amount == prize - share)));
+    assert_error(test.reveal(id, &carol, "9"), RiddleRushError::AnswerRevealDeadlinePassed);
+    assert_error(test.claim(id, &carol, None), RiddleRushError::AnswerMismatch);
+    let challenge = test.challenge(id);
+    assert_eq!((challenge.correct_submissions, challenge.revealed_count, challenge.claims_count), (2, 2, 2));
+    assert_eq!(challenge.total_paid_out, prize);
+}
+
+#[test]
+fn submission_rent_is_returned() {
+    let mut test = Test::new();
+    let setter = test.user();
+    let [winner, loser, absent, late, cranker] = [test.user(), test.user(), test.user(), test.user(), test.user()];
+    let id = test.create_expression(&setter, "3 + 4");
+    for (player, answer) in [(&winner, "7"), (&loser, "8"), (&absent, "7"), (&late, "7")] {
+        test.submit(id, player, answer, None).unwrap();
+    }
+    test.svm.warp_to(SUBMISSION_DEADLINE + 1);
+    test.reveal_solution(&setter, id, "", [0; 32]).unwrap();
+    for (player, answer) in [(&winner, "7"), (&loser, "8"), (&late, "7")] {
+        test.reveal(id, player, answer).unwrap();
+    }
+    assert_error(test.svm.process(&close_submission(&loser, id), &[&loser]), RiddleRushError::WithdrawTooEarly);
+
+    // Only submissions that can't claim anything may be closed during the claim window
+    test.svm.warp_to(ANSWER_REVEAL_DEADLINE + 1);
+    assert_error(test.svm.process(&close_submission(&winner, id), &[&winner]), RiddleRushError::SubmissionStillClaimable);
+    test.claim(id, &winner, None).unwrap();
+    for player in [loser, absent] {
+        let balance = test.svm.lamports(&player);
+        let events = test.svm.process(&close_submission(&player, id), &[&player]).unwrap();
+        assert!(matches!(events[..], [ProgramEvent::SubmissionClosed(ref e)] if e.submitter == player && e.lamports == submission_rent()));
+        assert_eq!(test.svm.lamports(&player), balance + submission_rent());
+        assert!(test.svm.account(&submission_address(&challenge_address(id), &player)).is_none());
+    }
+
+    // Winners who missed the claim window are swept by anyone once it is over, rent to the submitter
+    let result = test.svm.process(&sweep_submission(&cranker, id, &late), &[&cranker]);
+    assert_error(result, RiddleRushError::WithdrawTooEarly);
+    test.svm.warp_to(CLAIM_DEADLINE + 1);
+    let result = test.svm.process(&sweep_submission(&cranker, id, &winner), &[&cranker]);
+    assert_eq!(result.err(), Some(account_not_initialized()));
+    test.svm.process(&setter_close_challenge(&setter, id, None), &[&setter]).unwrap();
+    let [late_balance, cranker_balance] = [test.svm.lamports(&late), test.svm.lamports(&cranker)];
+    test.svm.process(&sweep_submission(&cranker, id, &late), &[&cranker]).unwrap();
+    assert_eq!(test.svm.lamports(&late), late_balance + submission_rent());
+    assert_eq!(test.svm.lamports(&cranker), cranker_balance);
+}
+
+#[test]
+fn payout_strategies() {
+    let mut test = Test::new();
+    let setter = test.user();
+    let players = [test.user(), test.user(), test.user(), test.user(), test.user()];
+    // The lottery is the third challenge, its seed hash is bound to that address
+    let seed = [0x3c; 32];
+    let strategies = [
+        PayoutStrategy::WinnerTakesAll,
+        PayoutStrategy::TopK { weights: vec![5, 3, 2] },
+        PayoutStrategy::Lottery { seed_hash: lottery_seed_commitment(&challenge_address(2), &seed) },
+    ];
+
+    let mut args = expression_args("1 + 1");
+    args.payout_strategy = PayoutStrategy::TopK { weights: vec![3, 0] };
+    assert_error(test.svm.process(&create_challenge(&setter, 0, args, None), &[&setter]), RiddleRushError::InvalidPayoutWeights);
+    let mut args = expression_args("1 + 1");
+    args.payout_strategy = PayoutStrategy::Lottery { seed_hash: [0; 32] };
+    assert_error(test.svm.process(&create_challenge(&setter, 0, args, None), &[&setter]), RiddleRushError::MissingLotterySeedHash);
+
+    let mut ids = Vec::new();
+    for strategy in &strategies {
+        let mut args = expression_args("1 + 1");
+        args.payout_strategy = strategy.clone();
+        ids.push(test.create(&setter, args, None));
+    }
+    // Players commit in order, the fourth one is wrong
+    for id in &ids {
+        for (index, player) in players.iter().enumerate() {
+            test.submit(*id, player, if index == 3 { "3" } else { "2" }, None).unwrap();
+            assert_eq!(test.submission(*id, player).commit_index, index as u64);
+        }
+    }
+
+    // Answers are revealed in reverse, ranks still follow the commit order
+    test.svm.warp_to(SUBMISSION_DEADLINE + 1);
+    assert_error(test.reveal_solution(&setter, ids[2], "", [0; 32]), RiddleRushError::LotterySeedMismatch);
+    for id in &ids {
+        test.reveal_solution(&setter, *id, "", if *id == ids[2] { seed } else { [0; 32] }).unwrap();
+        for (index, player) in players.iter().enumerate().rev() {
+            test.reveal(*id, player, if index == 3 { "3" } else { "2" }).unwrap();
+        }
+    }
+    let pot = 6 * ENTRY_FEE;
+    let prize = pot - pot * SETTER_CUT_BPS as u64 / 10_000 - pot * PROTOCOL_FEE_BPS as u64 / 10_000;
+    assert_eq!(test.challenge(ids[0]).ranked_submissions, [0]);
+    assert_eq!(test.challenge(ids[1]).ranked_submissions, [0, 1, 2]);
+    // The setter's seed is mixed with the salts of the correct reveals only
+    let mut mixed = seed;
+    for player in [players[0], players[1], players[2], players[4]] {
+        mixed.iter_mut().zip(nonce(&player)).for_each(|(byte, salt)| *byte ^= salt);
+    }
+    assert_eq!(test.challenge(ids[2]).lottery_seed, mixed);
+    assert_eq!(test.submission(ids[2], &players[4]).correct_index, 0);
+    assert_eq!(test.submission(ids[2], &players[0]).correct_index, 3);
+
+    test.svm.warp_to(ANSWER_REVEAL_DEADLINE + 1);
+    let claim = |test: &mut Test, id: u64, player: &Pubkey| match test.claim(id, player, None) {
+        Ok(events) => match events[..] {
+            [ProgramEvent::SubmitterClaimed(ref e)] => Ok(e.amount),
+            _ => panic!("expected a SubmitterClaimed event"),
+        },
+        Err(e) => Err(e),
+    };
+
+    // Winner takes all: only the earliest commit
+    assert_eq!(claim(&mut test, ids[0], &players[0]), Ok(prize));
+    for player in &players[1..] {
+        let result = claim(&mut test, ids[0], player).map(|_| ());
+        let expected = if *player == players[3] { RiddleRushError::AnswerMismatch } else { RiddleRushError::NotAWinner };
+        assert_eq!(result, Err(program_error(expected)));
+    }
+
+    // Top-K: the three earliest correct commits, weighted 5:3:2
+    let expected = [prize * 5 / 10, prize * 3 / 10, prize * 2 / 10];
+    for (player, share) in players.iter().zip(expected) {
+        assert_eq!(claim(&mut test, ids[1], player), Ok(share));
+    }
+    assert_eq!(claim(&mut test, ids[1], &players[4]), Err(program_error(RiddleRushError::NotAWinner)));
+
+    // Lottery: exactly one correct answer, the drawn one, wins the whole prize
+    let draw = test.challenge(ids[2]).lottery_draw();
+    let mut winners = Vec::new();
+    for player in [players[0], players[1], players[2], players[4]] {
+        let correct_index = test.submission(ids[2], &player).correct_index;
+        match claim(&mut test, ids[2], &player) {
+            Ok(share) => winners.push((correct_index, share)),
+            Err(e) => assert_eq!(e, program_error(RiddleRushError::NotAWinner)),
+        }
+    }
+    assert_eq!(winners, [(draw, prize)]);
+}
+
+#[test]
+fn unresolved_challenge_is_refunded() {
+    let mut test = Test::new();
+    let setter = test.user();
+    let [alice, bob, cranker] = [test.user(), test.user(), test.user()];
+    let mut args = expression_args("What walks on four legs in the morning?");
+    args.kind = ChallengeKind::TextRiddle;
+    let salt = [1; 32];
+    args.solution_hash = solution_commitment(&challenge_address(0), "A human", &salt);
+    let id = test.create(&setter, args, None);
+    let resolved = test.create_expression(&setter, "2 + 2");
+    for player in [alice, bob] {
+        test.submit(id, &player, "A human", None).unwrap();
+        test.submit(resolved, &player, "4", None).unwrap();
+    }
+
+    // The setter never reveals the solution
+    test.svm.warp_to(SUBMISSION_DEADLINE + 1);
+    test.reveal_solution(&cranker, resolved, "", [0; 32]).unwrap();
+    assert_error(test.svm.process(&refund(&cranker, id, &alice, None), &[&cranker]), RiddleRushError::WithdrawTooEarly);
+
+    test.svm.warp_to(ANSWER_REVEAL_DEADLINE + 1);
+    assert_error(test.reveal_solution(&setter, id, "A human", salt), RiddleRushError::SolutionRevealDeadlinePassed);
+    assert_error(test.reveal(id, &alice, "A human"), RiddleRushError::SolutionNotRevealed);
+    assert_error(test.claim(id, &alice, None), RiddleRushError::SolutionNotRevealed);
+    assert_error(test.svm.process(&setter_claim(&setter, id, None), &[&setter]), RiddleRushError::SolutionNotRevealed);
+    let treasury = test.treasury;
+    let result = test.svm.process(&treasury_claim(&cranker, &treasury, id, None), &[&cranker]);
+    assert_error(result, RiddleRushError::SolutionNotRevealed);
+    assert_error(test.svm.process(&refund(&cranker, resolved, &alice, None), &[&cranker]), RiddleRushError::ChallengeResolved);
+
+    // Anyone can refund a player, who gets the whole entry fee and the submission's rent back
+    let [alice_balance, cranker_balance] = [test.svm.lamports(&alice), test.svm.lamports(&cranker)];
+    let events = test.svm.process(&refund(&cranker, id, &alice, None), &[&cranker]).unwrap();
+    assert!(matches!(events[..], [ProgramEvent::SubmissionRefunded(ref e)] if e.submitter == alice && e.amount == ENTRY_FEE));
+    assert_eq!(test.svm.lamports(&alice), alice_balance + ENTRY_FEE + submission_rent());
+    assert_eq!(test.svm.lamports(&cranker), cranker_balance);
+    let result = test.svm.process(&refund(&cranker, id, &alice, None), &[&cranker]);
+    assert_eq!(result.err(), Some(account_not_initialized()));
+    let bob_balance = test.svm.lamports(&bob);
+    test.svm.process(&refund(&bob, id, &bob, None), &[&bob]).unwrap();
+    assert_eq!(test.svm.lamports(&bob), bob_balance + ENTRY_FEE + submission_rent());
+
+    // The setter only gets their own entry fee back when closing
+    test.svm.warp_to(CLAIM_DEADLINE + 1);
+    let challenge_rent = rent_exempt(test.svm.account(&challenge_address(id)).unwrap().data.len());
+    assert_eq!(test.svm.lamports(&challenge_address(id)), challenge_rent + ENTRY_FEE);
+    let setter_balance = test.svm.lamports(&setter);
+    test.svm.process(&setter_close_challenge(&setter, id, None), &[&setter]).unwrap();
+    assert_eq!(test.svm.lamports(&setter), setter_balance + challenge_rent + ENTRY_FEE);
+}
+
+#[test]
+fn token_challenge_lifecycle() {
+    let mut test = Test::new();
+    let mint_authority = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    test.svm.set_account(mint, mint_account(&mint_authority, 6));
+    let setter = test.user();
+    let [alice, bob] = [test.user(), test.user()];
+    let fee = 5_000_000;
+
+    // Every user holds a token account of the mint, funded with a few entry fees
+    let mut wallets = Vec::new();
+    for user in [setter, alice, bob, test.treasury] {
+        let address = Pubkey::new_unique();
+        test.svm.set_account(address, token_account(&mint, &user, 10 * fee));
+        wallets.push(TokenAccounts { mint, user_token_account: address, token_program: spl_token::ID });
+    }
+    let [setter_tokens, alice_tokens, bob_tokens, treasury_tokens] = wallets[..] else { unreachable!() };
+
+    let mut args = expression_args("(1 + 2) * 3");
+    args.entry_fee = fee;
+    let id = test.create(&setter, args, Some(setter_tokens));
+    let vault = vault_address(&challenge_address(id));
+    let challenge = test.challenge(id);
+    assert_eq!(challenge.mint, Some(mint));
+    assert_eq!(token_balance(&test.svm, &vault), fee);
+    let vault_owner = spl_token::state::Account::unpack(&test.svm.account(&vault).unwrap().data).unwrap().owner;
+    assert_eq!(vault_owner, challenge_address(id));
+
+    assert_error(test.submit(id, &alice, "9", None), RiddleRushError::TokenAccountsMismatch);
+    test.submit(id, &alice, "9", Some(alice_tokens)).unwrap();
+    test.submit(id, &bob, "6", Some(bob_tokens)).unwrap();
+    assert_eq!(token_balance(&test.svm, &vault), 3 * fee);
+
+    test.svm.warp_to(SUBMISSION_DEADLINE + 1);
+    test.reveal_solution(&bob, id, "", [0; 32]).unwrap();
+    test.reveal(id, &alice, "9").unwrap();
+    test.reveal(id, &bob, "6").unwrap();
+
+    test.svm.warp_to(ANSWER_REVEAL_DEADLINE + 1);
+    let pot = 3 * fee;
+    let setter_cut = pot * SETTER_CUT_BPS as u64 / 10_000;
+    let protocol_fee = pot * PROTOCOL_FEE_BPS as u64 / 10_000;
+    assert_error(test.claim(id, &bob, Some(bob_tokens)), RiddleRushError::AnswerMismatch);
+    test.claim(id, &alice, Some(alice_tokens)).unwrap();
+    assert_eq!(token_balance(&test.svm, &alice_tokens.user_token_account), 9 * fee + pot - setter_cut - protocol_fee);
+    test.svm.process(&setter_claim(&setter, id, Some(setter_tokens)), &[&setter]).unwrap();
+    let treasury = test.treasury;
+    test.svm.process(&treasury_claim(&bob, &treasury, id, Some(treasury_tokens)), &[&bob]).unwrap();
+    assert_eq!(token_balance(&test.svm, &treasury_tokens.user_token_account), 10 * fee + protocol_fee);
+    assert_eq!(token_balance(&test.svm, &vault), 0);
+
+    // Closing returns the vault's rent along with the challenge's
+    test.svm.warp_to(CLAIM_DEADLINE + 1);
+    let lamports = test.svm.lamports(&challenge_address(id)) + test.svm.lamports(&vault);
+    let balance = test.svm.lamports(&setter);
+    test.svm.process(&setter_close_challenge(&setter, id, Some(setter_tokens)), &[&setter]).unwrap();
+    assert_eq!(test.svm.lamports(&setter), balance + lamports);
+    assert_eq!(token_balance(&test.svm, &setter_tokens.user_token_account), 9 * fee + setter_cut);
+    assert!(test.svm.account(&vault).is_none());
+    assert!(test.svm.account(&challenge_address(id)).is_none());
+}
+
+#[test]
+fn mints_that_control_the_vault_are_rejected() {
+    let mut test = Test::new();
+    let setter = test.user();
+    let authority = Pubkey::new_unique();
+    let fee = 5_000_000;
+    let mut args = expression_args("2 + 2");
+    args.entry_fee = fee;
+
+    // A freeze authority could freeze the vault
+    let mut frozen = mint_account(&authority, 6);
+    let mut state = spl_token::state::Mint::unpack(&frozen.data).unwrap();
+    state.freeze_authority = Some(authority).into();
+    state.pack_into_slice(&mut frozen.data);
+    let mut mints = vec![(frozen, token_account as fn(&Pubkey, &Pubkey, u64) -> Account, spl_token::ID)];
+    // A permanent delegate could drain it and non-transferable tokens could never be paid out
+    for extension in [ExtensionType::PermanentDelegate, ExtensionType::NonTransferable] {
+        mints.push((token_2022_mint_account(&authority, 6, extension), token_2022_account, spl_token_2022::ID));
+    }
+    for (mint_account, token_account, token_program) in mints {
+        let mint = Pubkey::new_unique();
+        test.svm.set_account(mint, mint_account);
+        let user_token_account = Pubkey::new_unique();
+        test.svm.set_account(user_token_account, token_account(&mint, &setter, 10 * fee));
+        let tokens = TokenAccounts { mint, user_token_account, token_program };
+        let result = test.svm.process(&create_challenge(&setter, 0, args.clone(), Some(tokens)), &[&setter]);
+        assert_error(result, RiddleRushError::UnsupportedMint);
+    }
+
+    // Transfer fees are accounted for
+    let mint = Pubkey::new_unique();
+    test.svm.set_account(mint, token_2022_mint_account(&authority, 6, ExtensionType::TransferFeeConfig));
+    let user_token_account = Pubkey::new_unique();
+    test.svm.set_account(user_token_account, token_2022_account(&mint, &setter, 10 * fee));
+    let tokens = TokenAccounts { mint, user_token_account, token_program: spl_token_2022::ID };
+    let id = test.create(&setter, args, Some(tokens));
+    assert_eq!(test.challenge(id).mint, Some(mint));
+}
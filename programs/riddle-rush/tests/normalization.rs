@@ -0,0 +1,65 @@
+// Answer normalization: each option on its own, in combination, and the cases it deliberately
+// doesn't cover
+use riddle_rush::AnswerNormalization;
+
+fn only(configure: impl FnOnce(&mut AnswerNormalization)) -> AnswerNormalization {
+    let mut normalization = AnswerNormalization::default();
+    configure(&mut normalization);
+    normalization
+}
+
+#[test]
+fn nothing_is_normalized_by_default() {
+    let normalization = AnswerNormalization::default();
+    assert!(normalization.accepts("42", "42"));
+    assert!(!normalization.accepts("42", " 42"));
+    assert!(!normalization.accepts("A map", "a map"));
+    assert!(!normalization.accepts("12", "１２"));
+    assert!(!normalization.accepts("1/2", "0.5"));
+}
+
+#[test]
+fn nfkc_folds_compatibility_characters() {
+    let normalization = only(|n| n.nfkc = true);
+    // Full-width digits and letters, ligatures and superscripts
+    assert!(normalization.accepts("12", "１２"));
+    assert!(normalization.accepts("fine", "ﬁne"));
+    assert!(normalization.accepts("Map", "Ｍａｐ"));
+    assert!(normalization.accepts("x2", "x²"));
+    // Composed and decomposed accents
+    assert!(normalization.accepts("café", "cafe\u{301}"));
+    assert!(!normalization.accepts("cafe", "café"));
+}
+
+#[test]
+fn numeric_compares_by_value() {
+    let normalization = only(|n| n.numeric = true);
+    assert!(normalization.accepts("1/2", "0.5"));
+    assert!(normalization.accepts("0.5", "2/4"));
+    assert!(normalization.accepts("-1/2", "-.50"));
+    assert!(normalization.accepts("18", "+18.0"));
+    assert!(normalization.accepts("18", "36/2"));
+    assert!(!normalization.accepts("1/3", "0.333"));
+    // Anything that isn't a plain number is still compared as text
+    assert!(normalization.accepts("half", "half"));
+    assert!(!normalization.accepts("0.5", "half"));
+    assert!(!normalization.accepts("18", " 18"));
+}
+
+#[test]
+fn lowercase_is_not_full_case_folding() {
+    let normalization = only(|n| n.lowercase = true);
+    assert!(normalization.accepts("A Map", "a MAP"));
+    assert!(normalization.accepts("ΣΟΦΙΑ", "σοφια"));
+    // Full case folding would match these, lowercasing each character doesn't
+    assert!(!normalization.accepts("STRASSE", "straße"));
+    assert!(!normalization.accepts("σοφος", "σοφοσ"));
+}
+
+#[test]
+fn options_combine() {
+    let normalization = AnswerNormalization { trim: true, lowercase: true, nfkc: true, numeric: true, alternates: Vec::new() };
+    assert!(normalization.accepts("1/2", "  ０.５ "));
+    assert!(normalization.accepts("A map", " Ａ ＭＡＰ"));
+    assert!(!normalization.accepts("A map", "A globe"));
+}
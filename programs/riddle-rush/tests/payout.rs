@@ -0,0 +1,106 @@
+// Rounding of prize shares under each payout strategy: shares are rounded down, never add up to more
+// than the prize, and leave less than one base unit of dust per winner
+use riddle_rush::{PayoutStrategy, MAX_PAYOUT_RANKS};
+
+const PRIZES: [u64; 8] = [0, 1, 7, 10, 999, 1_000_001, 3_600_000_007, u64::MAX];
+
+fn top_k(weights: &[u16]) -> PayoutStrategy {
+    PayoutStrategy::TopK { weights: weights.to_vec() }
+}
+
+// Shares of every correct answer, assuming the first ranked answers are the earliest commits
+fn shares(strategy: &PayoutStrategy, prize: u64, correct: u64, drawn: u64) -> Vec<u64> {
+    (0..correct)
+        .map(|index| {
+            let rank = (index < strategy.ranks() as u64).then_some(index as usize);
+            strategy.share(prize, correct, rank, index == drawn)
+        })
+        .collect()
+}
+
+#[track_caller]
+fn assert_rounded_down(shares: &[u64], prize: u64, winners: usize) {
+    let paid: u128 = shares.iter().map(|share| *share as u128).sum();
+    assert!(paid <= prize as u128, "paid {} out of {}", paid, prize);
+    assert!(prize as u128 - paid < winners.max(1) as u128, "{} of dust for {} winners", prize as u128 - paid, winners);
+}
+
+#[test]
+fn equal_split_rounds_down() {
+    let strategy = PayoutStrategy::EqualSplit;
+    assert_eq!(shares(&strategy, 10, 3, 0), [3, 3, 3]);
+    assert_eq!(shares(&strategy, 2, 3, 0), [0, 0, 0]);
+    assert_eq!(strategy.share(10, 0, None, false), 0);
+    for prize in PRIZES {
+        for correct in 1..=25 {
+            assert_rounded_down(&shares(&strategy, prize, correct, 0), prize, correct as usize);
+        }
+    }
+}
+
+#[test]
+fn winner_takes_all_pays_the_earliest_commit() {
+    let strategy = PayoutStrategy::WinnerTakesAll;
+    assert_eq!(strategy.ranks(), 1);
+    assert_eq!(shares(&strategy, 1_000, 4, 2), [1_000, 0, 0, 0]);
+    assert_eq!(strategy.share(1_000, 4, None, true), 0);
+    for prize in PRIZES {
+        assert_eq!(shares(&strategy, prize, 3, 0).iter().sum::<u64>(), prize);
+    }
+}
+
+#[test]
+fn lottery_pays_the_drawn_answer() {
+    let strategy = PayoutStrategy::Lottery { seed_hash: [1; 32] };
+    assert_eq!(strategy.ranks(), 0);
+    assert_eq!(shares(&strategy, 1_000, 4, 2), [0, 0, 1_000, 0]);
+    for prize in PRIZES {
+        assert_eq!(shares(&strategy, prize, 5, 4).iter().sum::<u64>(), prize);
+    }
+}
+
+#[test]
+fn top_k_splits_by_weight() {
+    let strategy = top_k(&[5, 3, 2]);
+    assert_eq!(strategy.ranks(), 3);
+    assert_eq!(shares(&strategy, 1_000, 5, 0), [500, 300, 200, 0, 0]);
+    // 1_000_001 * 5 / 10, 1_000_001 * 3 / 10 and 1_000_001 * 2 / 10 leave one unit of dust
+    assert_eq!(shares(&strategy, 1_000_001, 3, 0), [500_000, 300_000, 200_000]);
+    // Unfilled ranks give their weight back to the filled ones
+    assert_eq!(shares(&strategy, 800, 2, 0), [500, 300]);
+    assert_eq!(shares(&strategy, 1_000, 1, 0), [1_000]);
+    assert_eq!(strategy.share(1_000, 5, None, false), 0);
+    assert_eq!(strategy.share(1_000, 5, Some(3), false), 0);
+}
+
+#[test]
+fn top_k_never_pays_more_than_the_prize() {
+    let tables: [&[u16]; 6] = [
+        &[1],
+        &[1, 1, 1],
+        &[5, 3, 2],
+        &[u16::MAX, 1],
+        &[7, 11, 13, 17, 19, 23, 29, 31, 37, 41],
+        &[u16::MAX; MAX_PAYOUT_RANKS],
+    ];
+    for weights in tables {
+        let strategy = top_k(weights);
+        for prize in PRIZES {
+            for correct in 1..=MAX_PAYOUT_RANKS as u64 + 2 {
+                let winners = weights.len().min(correct as usize);
+                assert_rounded_down(&shares(&strategy, prize, correct, 0), prize, winners);
+            }
+        }
+    }
+}
+
+#[test]
+fn strategies_are_validated() {
+    assert!(top_k(&[]).validate().is_err());
+    assert!(top_k(&[3, 0, 1]).validate().is_err());
+    assert!(top_k(&[1; MAX_PAYOUT_RANKS + 1]).validate().is_err());
+    assert!(top_k(&[1; MAX_PAYOUT_RANKS]).validate().is_ok());
+    assert!(PayoutStrategy::WinnerTakesAll.validate().is_ok());
+    assert!(PayoutStrategy::Lottery { seed_hash: [0; 32] }.validate().is_err());
+    assert!(PayoutStrategy::Lottery { seed_hash: [1; 32] }.validate().is_ok());
+}
@@ -47,6 +47,13 @@ async function main() {
             program.programId
         );
 
+        // The challenge PDA uses the next id and the setter cut must fall in the configured range
+        const globalConfig = await (program.account as any).globalConfig.fetch(globalConfigPda);
+        const [challengePda] = PublicKey.findProgramAddressSync(
+            [Buffer.from('challenge'), globalConfig.nextChallengeId.toArrayLike(Buffer, 'le', 8)],
+            program.programId
+        );
+
         console.log('Creating challenge with parameters:');
         console.log('Question:', question);
         console.log('Entry fee:', entryFeeInSol, 'SOL');
@@ -61,12 +68,22 @@ async function main() {
                 new BN(submissionDeadline),
                 new BN(answerRevealDeadline),
                 new BN(claimDeadline),
-                new BN(entryFeeInLamports)
+                new BN(entryFeeInLamports),
+                { expression: {} },
+                Array(32).fill(0),
+                { trim: true, lowercase: false, nfkc: false, numeric: true, alternates: [] },
+                globalConfig.maxSetterCutBps,
+                { equalSplit: {} }
             )
-            .accounts({
+            .accountsPartial({
                 setter: wallet.publicKey,
                 globalConfig: globalConfigPda,
+                challengeAccount: challengePda,
                 systemProgram: SystemProgram.programId,
+                mint: null,
+                vault: null,
+                setterTokenAccount: null,
+                tokenProgram: null,
             })
             .rpc();
             
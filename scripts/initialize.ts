@@ -5,6 +5,13 @@ import * as path from 'path';
 import type { RiddleRush } from '../target/types/riddle_rush';
 
 async function main() {
+    // Usage: initialize [treasury] [protocol_fee_bps] [min_setter_cut_bps] [max_setter_cut_bps]
+    // with the same defaults as `riddle-cli init`
+    const args = process.argv.slice(2);
+    const protocolFeeBps = args[1] ? parseInt(args[1], 10) : 0;
+    const minSetterCutBps = args[2] ? parseInt(args[2], 10) : 1000;
+    const maxSetterCutBps = args[3] ? parseInt(args[3], 10) : 1000;
+
     // Connect to devnet
     const connection = new Connection('https://api.devnet.solana.com', 'confirmed');
     
@@ -28,16 +35,30 @@ async function main() {
             program.programId
         );
 
+        // Only the upgrade authority may initialize, which the program checks against the program data account
+        const [programDataPda] = PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+        );
+
+        // The treasury receives the protocol fee, the wallet when not set
+        const treasury = args[0] ? new PublicKey(args[0]) : wallet.publicKey;
+
         console.log('Initializing program...');
         console.log('Global Config PDA:', globalConfigPda.toBase58());
+        console.log('Treasury:', treasury.toBase58());
+        console.log('Protocol fee:', protocolFeeBps, 'bps');
+        console.log('Setter cut:', minSetterCutBps, '-', maxSetterCutBps, 'bps');
 
         // Initialize the program
-        const tx = await (program as any).methods
-            .initialize()
-            .accounts({
+        const tx = await program.methods
+            .initialize(treasury, protocolFeeBps, minSetterCutBps, maxSetterCutBps)
+            .accountsPartial({
                 authority: wallet.publicKey,
-                global_config: globalConfigPda,
-                system_program: SystemProgram.programId,
+                globalConfig: globalConfigPda,
+                program: program.programId,
+                programData: programDataPda,
+                systemProgram: SystemProgram.programId,
             })
             .rpc();
             